@@ -1,4 +1,4 @@
-use crate::fields::{extract_fields, field_args, field_initializers};
+use crate::fields::{default_initializers, extract_fields, field_args, field_initializers};
 use crate::methods::{implement_base_getters, implement_base_setters};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
@@ -10,7 +10,7 @@ fn has_role_field(input: &DeriveInput) -> bool {
             named_fields
                 .named
                 .iter()
-                .any(|field| field.ident.as_ref().map_or(false, |ident| ident == "role"))
+                .any(|field| field.ident.as_ref().is_some_and(|ident| ident == "role"))
         })
         .unwrap_or(false)
 }
@@ -19,6 +19,7 @@ fn implement_struct_new(input: &DeriveInput) -> Result<TokenStream2, Error> {
     let named_fields = extract_fields(input)?;
     let field_args = field_args(named_fields, &["base"]);
     let field_initializers = field_initializers(named_fields, &["base"]);
+    let default_initializers = default_initializers(named_fields);
     let message_type_name = extract_message_type_name(input);
 
     let (field_args_tokens, field_initializers_tokens) = if field_args.is_empty() {
@@ -30,15 +31,21 @@ fn implement_struct_new(input: &DeriveInput) -> Result<TokenStream2, Error> {
         )
     };
 
+    let default_initializers_tokens = if default_initializers.is_empty() {
+        quote! {}
+    } else {
+        quote! { , #(#default_initializers),* }
+    };
+
     Ok(quote! {
-        pub fn new(content: &str #field_args_tokens) -> Self {
+        pub fn new(content: impl Into<::messageforge::MessageContent> #field_args_tokens) -> Self {
             Self::new_with_example(content, false #field_initializers_tokens)
         }
 
-        pub fn new_with_example(content: &str, example: bool #field_args_tokens) -> Self {
+        pub fn new_with_example(content: impl Into<::messageforge::MessageContent>, example: bool #field_args_tokens) -> Self {
             Self {
                 base: BaseMessageFields {
-                    content: content.to_string(),
+                    content: content.into(),
                     example,
                     message_type: MessageType::#message_type_name,
                     additional_kwargs: std::collections::HashMap::new(),
@@ -47,6 +54,7 @@ fn implement_struct_new(input: &DeriveInput) -> Result<TokenStream2, Error> {
                     name: None,
                 }
                 #field_initializers_tokens
+                #default_initializers_tokens
             }
         }
     })
@@ -56,7 +64,8 @@ fn extract_message_type_name(input: &DeriveInput) -> Ident {
     let struct_name = &input.ident;
     let struct_name_str = struct_name.to_string();
     let message_type_str = struct_name_str
-        .strip_suffix("Message")
+        .strip_suffix("MessageChunk")
+        .or_else(|| struct_name_str.strip_suffix("Message"))
         .unwrap_or(&struct_name_str);
     format_ident!("{}", message_type_str)
 }
@@ -119,7 +128,11 @@ mod tests {
 
     fn base_message_impl_common() -> TokenStream2 {
         quote! {
-            fn content(&self) -> &str {
+            fn content(&self) -> std::borrow::Cow<'_, str> {
+                self.base.content.text()
+            }
+
+            fn message_content(&self) -> &::messageforge::MessageContent {
                 &self.base.content
             }
 
@@ -131,14 +144,22 @@ mod tests {
                 self.base.example
             }
 
-            fn additional_kwargs(&self) -> &std::collections::HashMap<String, String> {
+            fn additional_kwargs(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                 &self.base.additional_kwargs
             }
 
-            fn response_metadata(&self) -> &std::collections::HashMap<String, String> {
+            fn additional_kwargs_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+                &mut self.base.additional_kwargs
+            }
+
+            fn response_metadata(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                 &self.base.response_metadata
             }
 
+            fn response_metadata_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+                &mut self.base.response_metadata
+            }
+
             fn id(&self) -> Option<&str> {
                 self.base.id.as_deref()
             }
@@ -151,8 +172,8 @@ mod tests {
 
     fn base_message_setters() -> TokenStream2 {
         quote! {
-            pub fn set_content(&mut self, new_content: &str) {
-                self.base.content = new_content.to_string();
+            pub fn set_content(&mut self, new_content: impl Into<::messageforge::MessageContent>) {
+                self.base.content = new_content.into();
             }
 
             pub fn set_example(&mut self, example: bool) {
@@ -185,14 +206,14 @@ mod tests {
 
         let expected = quote! {
             impl HumanMessage {
-                pub fn new(content: &str, role: String) -> Self {
+                pub fn new(content: impl Into<::messageforge::MessageContent>, role: String) -> Self {
                     Self::new_with_example(content, false, role)
                 }
 
-                pub fn new_with_example(content: &str, example: bool, role: String) -> Self {
+                pub fn new_with_example(content: impl Into<::messageforge::MessageContent>, example: bool, role: String) -> Self {
                     Self {
                         base: BaseMessageFields {
-                            content: content.to_string(),
+                            content: content.into(),
                             example,
                             message_type: MessageType::Human,
                             additional_kwargs: std::collections::HashMap::new(),
@@ -234,14 +255,14 @@ mod tests {
 
         let expected = quote! {
             impl SystemMessage {
-                pub fn new(content: &str) -> Self {
+                pub fn new(content: impl Into<::messageforge::MessageContent>) -> Self {
                     Self::new_with_example(content, false)
                 }
 
-                pub fn new_with_example(content: &str, example: bool) -> Self {
+                pub fn new_with_example(content: impl Into<::messageforge::MessageContent>, example: bool) -> Self {
                     Self {
                         base: BaseMessageFields {
-                            content: content.to_string(),
+                            content: content.into(),
                             example,
                             message_type: MessageType::System,
                             additional_kwargs: std::collections::HashMap::new(),
@@ -285,14 +306,14 @@ mod tests {
 
         let expected = quote! {
             impl ToolMessage {
-                pub fn new(content: &str, tool_call_id: String, artifact: Option<String>, status: ToolStatus) -> Self {
+                pub fn new(content: impl Into<::messageforge::MessageContent>, tool_call_id: String, artifact: Option<String>, status: ToolStatus) -> Self {
                     Self::new_with_example(content, false, tool_call_id, artifact, status)
                 }
 
-                pub fn new_with_example(content: &str, example: bool, tool_call_id: String, artifact: Option<String>, status: ToolStatus) -> Self {
+                pub fn new_with_example(content: impl Into<::messageforge::MessageContent>, example: bool, tool_call_id: String, artifact: Option<String>, status: ToolStatus) -> Self {
                     Self {
                         base: BaseMessageFields {
-                            content: content.to_string(),
+                            content: content.into(),
                             example,
                             message_type: MessageType::Tool,
                             additional_kwargs: std::collections::HashMap::new(),
@@ -320,4 +341,66 @@ mod tests {
 
         assert_eq!(generated.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn test_struct_with_default_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct AiMessage {
+                #[base_message(default)]
+                tool_calls: Vec<ToolCall>,
+                base: BaseMessageFields,
+            }
+        };
+
+        let generated = derive_macro(quote! { #input });
+
+        let base_message_impl_common = base_message_impl_common();
+        let base_message_setters = base_message_setters();
+
+        let expected = quote! {
+            impl AiMessage {
+                pub fn new(content: impl Into<::messageforge::MessageContent>) -> Self {
+                    Self::new_with_example(content, false)
+                }
+
+                pub fn new_with_example(content: impl Into<::messageforge::MessageContent>, example: bool) -> Self {
+                    Self {
+                        base: BaseMessageFields {
+                            content: content.into(),
+                            example,
+                            message_type: MessageType::Ai,
+                            additional_kwargs: std::collections::HashMap::new(),
+                            response_metadata: std::collections::HashMap::new(),
+                            id: None,
+                            name: None,
+                        },
+                        tool_calls: Default::default()
+                    }
+                }
+
+                #base_message_setters
+            }
+
+            impl BaseMessage for AiMessage {
+                #base_message_impl_common
+
+                fn role(&self) -> &str {
+                    self.base.message_type.as_str()
+                }
+            }
+        };
+
+        assert_eq!(generated.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_chunk_struct_uses_base_message_type() {
+        let input: DeriveInput = parse_quote! {
+            struct HumanMessageChunk {
+                base: BaseMessageFields,
+            }
+        };
+
+        assert_eq!(extract_message_type_name(&input).to_string(), "Human");
+    }
 }
@@ -23,13 +23,23 @@ pub fn field_name_and_type(field: &Field) -> (&Option<Ident>, &Type) {
 
 fn is_excluded(name: &Option<Ident>, excludes: &[&str]) -> bool {
     name.as_ref()
-        .map_or(false, |n| excludes.contains(&n.to_string().as_str()))
+        .is_some_and(|n| excludes.contains(&n.to_string().as_str()))
+}
+
+pub fn is_default_field(field: &Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.path().is_ident("base_message")
+            && attr
+                .parse_args::<Ident>()
+                .is_ok_and(|ident| ident == "default")
+    })
 }
 
 pub fn field_args(fields: &FieldsNamed, excludes: &[&str]) -> Vec<proc_macro2::TokenStream> {
     fields
         .named
         .iter()
+        .filter(|field| !is_default_field(field))
         .map(field_name_and_type)
         .filter(|(name, _)| !is_excluded(name, excludes))
         .map(|(name, ty)| {
@@ -46,6 +56,7 @@ pub fn field_initializers(
     fields
         .named
         .iter()
+        .filter(|field| !is_default_field(field))
         .map(field_name_and_type)
         .filter(|(name, _)| !is_excluded(name, excludes))
         .map(|(name, _)| {
@@ -55,6 +66,18 @@ pub fn field_initializers(
         .collect()
 }
 
+pub fn default_initializers(fields: &FieldsNamed) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .named
+        .iter()
+        .filter(|field| is_default_field(field))
+        .map(|field| {
+            let name = field.ident.as_ref().unwrap();
+            quote! { #name: Default::default() }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -337,4 +360,48 @@ mod tests {
 
         assert_eq!(result.to_string(), expected_with_exclusion.to_string());
     }
+
+    #[test]
+    fn test_default_fields_are_excluded_from_args() {
+        let input: DeriveInput = parse_quote! {
+            struct TestStruct {
+                field1: String,
+                #[base_message(default)]
+                field2: Vec<u32>,
+            }
+        };
+
+        let fields = extract_fields(&input).unwrap();
+        let args = field_args(fields, &[]);
+        let initializers = field_initializers(fields, &[]);
+        let defaults = default_initializers(fields);
+
+        assert_eq!(
+            quote! { #(#args),* }.to_string(),
+            quote! { field1: String }.to_string()
+        );
+        assert_eq!(
+            quote! { #(#initializers),* }.to_string(),
+            quote! { field1 }.to_string()
+        );
+        assert_eq!(
+            quote! { #(#defaults),* }.to_string(),
+            quote! { field2: Default::default() }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_is_default_field_ignores_other_attributes() {
+        let input: DeriveInput = parse_quote! {
+            struct TestStruct {
+                #[serde(default)]
+                field1: String,
+                #[base_message(other)]
+                field2: String,
+            }
+        };
+
+        let fields = extract_fields(&input).unwrap();
+        assert!(fields.named.iter().all(|field| !is_default_field(field)));
+    }
 }
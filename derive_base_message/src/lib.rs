@@ -5,7 +5,7 @@ mod methods;
 use derive_macro::derive_macro;
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(BaseMessage)]
+#[proc_macro_derive(BaseMessage, attributes(base_message))]
 pub fn derive_base_message(input: TokenStream) -> TokenStream {
     derive_macro(input.into()).into()
 }
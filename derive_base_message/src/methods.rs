@@ -3,7 +3,11 @@ use quote::quote;
 
 pub fn implement_base_getters() -> TokenStream2 {
     quote! {
-        fn content(&self) -> &str {
+        fn content(&self) -> std::borrow::Cow<'_, str> {
+            self.base.content.text()
+        }
+
+        fn message_content(&self) -> &::messageforge::MessageContent {
             &self.base.content
         }
 
@@ -15,14 +19,22 @@ pub fn implement_base_getters() -> TokenStream2 {
             self.base.example
         }
 
-        fn additional_kwargs(&self) -> &std::collections::HashMap<String, String> {
+        fn additional_kwargs(&self) -> &std::collections::HashMap<String, serde_json::Value> {
             &self.base.additional_kwargs
         }
 
-        fn response_metadata(&self) -> &std::collections::HashMap<String, String> {
+        fn additional_kwargs_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+            &mut self.base.additional_kwargs
+        }
+
+        fn response_metadata(&self) -> &std::collections::HashMap<String, serde_json::Value> {
             &self.base.response_metadata
         }
 
+        fn response_metadata_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+            &mut self.base.response_metadata
+        }
+
         fn id(&self) -> Option<&str> {
             self.base.id.as_deref()
         }
@@ -35,8 +47,8 @@ pub fn implement_base_getters() -> TokenStream2 {
 
 pub fn implement_base_setters() -> TokenStream2 {
     quote! {
-        pub fn set_content(&mut self, new_content: &str) {
-            self.base.content = new_content.to_string();
+        pub fn set_content(&mut self, new_content: impl Into<::messageforge::MessageContent>) {
+            self.base.content = new_content.into();
         }
 
         pub fn set_example(&mut self, example: bool) {
@@ -64,7 +76,11 @@ mod tests {
         let generated = implement_base_getters();
 
         let expected = quote! {
-            fn content(&self) -> &str {
+            fn content(&self) -> std::borrow::Cow<'_, str> {
+                self.base.content.text()
+            }
+
+            fn message_content(&self) -> &::messageforge::MessageContent {
                 &self.base.content
             }
 
@@ -76,14 +92,22 @@ mod tests {
                 self.base.example
             }
 
-            fn additional_kwargs(&self) -> &std::collections::HashMap<String, String> {
+            fn additional_kwargs(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                 &self.base.additional_kwargs
             }
 
-            fn response_metadata(&self) -> &std::collections::HashMap<String, String> {
+            fn additional_kwargs_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+                &mut self.base.additional_kwargs
+            }
+
+            fn response_metadata(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                 &self.base.response_metadata
             }
 
+            fn response_metadata_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+                &mut self.base.response_metadata
+            }
+
             fn id(&self) -> Option<&str> {
                 self.base.id.as_deref()
             }
@@ -101,8 +125,8 @@ mod tests {
         let generated = super::implement_base_setters();
 
         let expected = quote! {
-            pub fn set_content(&mut self, new_content: &str) {
-                self.base.content = new_content.to_string();
+            pub fn set_content(&mut self, new_content: impl Into<::messageforge::MessageContent>) {
+                self.base.content = new_content.into();
             }
 
             pub fn set_example(&mut self, example: bool) {
@@ -1,14 +1,70 @@
 use crate::prelude::*;
+use crate::tool_call::{InvalidToolCall, ToolCall};
+use crate::usage_metadata::UsageMetadata;
+use derive_base_message::BaseMessage;
 
-define_message!(Ai);
+#[derive(BaseMessage, Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AiMessage {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[base_message(default)]
+    pub tool_calls: Vec<ToolCall>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[base_message(default)]
+    pub invalid_tool_calls: Vec<InvalidToolCall>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[base_message(default)]
+    pub usage_metadata: Option<UsageMetadata>,
+
+    #[serde(flatten)]
+    pub base: BaseMessageFields,
+}
+
+impl AiMessage {
+    pub fn new_with_tool_calls(
+        content: impl Into<MessageContent>,
+        tool_calls: Vec<ToolCall>,
+    ) -> Self {
+        let mut message = Self::new(content);
+        message.tool_calls = tool_calls;
+        message
+    }
+
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        &self.tool_calls
+    }
+
+    pub fn invalid_tool_calls(&self) -> &[InvalidToolCall] {
+        &self.invalid_tool_calls
+    }
+
+    pub fn has_tool_calls(&self) -> bool {
+        !self.tool_calls.is_empty() || !self.invalid_tool_calls.is_empty()
+    }
+
+    pub fn set_tool_calls(&mut self, tool_calls: Vec<ToolCall>) {
+        self.tool_calls = tool_calls;
+    }
+
+    pub fn set_invalid_tool_calls(&mut self, invalid_tool_calls: Vec<InvalidToolCall>) {
+        self.invalid_tool_calls = invalid_tool_calls;
+    }
+
+    pub fn usage_metadata(&self) -> Option<&UsageMetadata> {
+        self.usage_metadata.as_ref()
+    }
+
+    pub fn set_usage_metadata(&mut self, usage_metadata: Option<UsageMetadata>) {
+        self.usage_metadata = usage_metadata;
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::{json, Value};
 
-    define_message!(MessageType::Ai);
-
     #[test]
     fn test_aimessage_creation() {
         let mut ai_message = AiMessage::new("This is an AI message.");
@@ -46,11 +102,11 @@ mod tests {
         ai_message
             .base
             .additional_kwargs
-            .insert("key".to_string(), "value".to_string());
+            .insert("key".to_string(), "value".into());
 
         assert_eq!(
             ai_message.additional_kwargs().get("key"),
-            Some(&"value".to_string())
+            Some(&"value".into())
         );
     }
 
@@ -60,11 +116,11 @@ mod tests {
         ai_message
             .base
             .response_metadata
-            .insert("source".to_string(), "AI Model".to_string());
+            .insert("source".to_string(), "AI Model".into());
 
         assert_eq!(
             ai_message.response_metadata().get("source"),
-            Some(&"AI Model".to_string())
+            Some(&"AI Model".into())
         );
     }
 
@@ -98,7 +154,7 @@ mod tests {
     fn test_aimessage_debug_format() {
         let ai_message = AiMessage::new("Debug AI message.");
         let debug_output = format!("{:?}", ai_message);
-        let expected_debug_output = r#"AiMessage { base: BaseMessageFields { content: "Debug AI message.", example: false, message_type: Ai, additional_kwargs: {}, response_metadata: {}, id: None, name: None } }"#;
+        let expected_debug_output = r#"AiMessage { tool_calls: [], invalid_tool_calls: [], usage_metadata: None, base: BaseMessageFields { content: "Debug AI message.", example: false, message_type: Ai, additional_kwargs: {}, response_metadata: {}, id: None, name: None } }"#;
         assert_eq!(debug_output, expected_debug_output);
     }
 
@@ -110,21 +166,21 @@ mod tests {
         ai_message
             .base
             .additional_kwargs
-            .insert("task".to_string(), "information retrieval".to_string());
+            .insert("task".to_string(), "information retrieval".into());
         ai_message
             .base
             .response_metadata
-            .insert("model".to_string(), "gpt-3".to_string());
+            .insert("model".to_string(), "gpt-3".into());
 
         assert_eq!(ai_message.id(), Some("AI123"));
         assert_eq!(ai_message.name(), Some("AI Assistant"));
         assert_eq!(
             ai_message.additional_kwargs().get("task"),
-            Some(&"information retrieval".to_string())
+            Some(&"information retrieval".into())
         );
         assert_eq!(
             ai_message.response_metadata().get("model"),
-            Some(&"gpt-3".to_string())
+            Some(&"gpt-3".into())
         );
 
         let expected_json = json!({
@@ -144,4 +200,90 @@ mod tests {
         let serialized: Value = serde_json::to_value(&ai_message).unwrap();
         assert_eq!(serialized, expected_json);
     }
+
+    #[test]
+    fn test_aimessage_with_tool_calls_serialization() {
+        let mut ai_message = AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "get_weather",
+                json!({"city": "Paris"}),
+                Some("call_1".to_string()),
+            )],
+        );
+        ai_message.set_invalid_tool_calls(vec![InvalidToolCall::new(
+            Some("get_time".to_string()),
+            Some("{\"tz\":".to_string()),
+            Some("call_2".to_string()),
+            Some("EOF while parsing".to_string()),
+        )]);
+        assert!(ai_message.has_tool_calls());
+
+        let expected_json = json!({
+            "tool_calls": [{
+                "type": "tool_call",
+                "name": "get_weather",
+                "args": {"city": "Paris"},
+                "id": "call_1"
+            }],
+            "invalid_tool_calls": [{
+                "type": "invalid_tool_call",
+                "name": "get_time",
+                "args": "{\"tz\":",
+                "id": "call_2",
+                "error": "EOF while parsing"
+            }],
+            "content": "",
+            "example": false,
+            "message_type": "Ai"
+        });
+
+        let serialized: Value = serde_json::to_value(&ai_message).unwrap();
+        assert_eq!(serialized, expected_json);
+
+        let deserialized: AiMessage = serde_json::from_value(expected_json).unwrap();
+        assert_eq!(deserialized, ai_message);
+    }
+
+    #[test]
+    fn test_aimessage_tool_call_typed_args() {
+        #[derive(Deserialize)]
+        struct WeatherArgs {
+            city: String,
+        }
+
+        let ai_message = AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new("get_weather", json!({"city": "Paris"}), None)],
+        );
+
+        let args: WeatherArgs = ai_message.tool_calls()[0].parse_args().unwrap();
+        assert_eq!(args.city, "Paris");
+    }
+
+    #[test]
+    fn test_aimessage_with_usage_metadata() {
+        let mut ai_message = AiMessage::new("This is an AI message.");
+        let mut usage = UsageMetadata::new(12, 8);
+        usage.set_reasoning_tokens(2);
+        ai_message.set_usage_metadata(Some(usage));
+
+        let expected_json = json!({
+            "usage_metadata": {
+                "input_tokens": 12,
+                "output_tokens": 8,
+                "total_tokens": 20,
+                "output_token_details": {"reasoning": 2}
+            },
+            "content": "This is an AI message.",
+            "example": false,
+            "message_type": "Ai"
+        });
+
+        let serialized: Value = serde_json::to_value(&ai_message).unwrap();
+        assert_eq!(serialized, expected_json);
+
+        let deserialized: AiMessage = serde_json::from_value(expected_json).unwrap();
+        assert_eq!(deserialized.usage_metadata(), Some(&usage));
+    }
 }
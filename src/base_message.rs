@@ -1,14 +1,16 @@
 use std::{
+    borrow::Cow,
     collections::HashMap,
     fmt::{self, Debug},
 };
 
-use crate::MessageType;
+use crate::{MessageContent, MessageType};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct BaseMessageFields {
-    pub content: String,
+    pub content: MessageContent,
 
     #[serde(default)]
     pub example: bool,
@@ -16,10 +18,10 @@ pub struct BaseMessageFields {
     pub message_type: MessageType,
 
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
-    pub additional_kwargs: HashMap<String, String>,
+    pub additional_kwargs: HashMap<String, Value>,
 
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
-    pub response_metadata: HashMap<String, String>,
+    pub response_metadata: HashMap<String, Value>,
 
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub id: Option<String>,
@@ -29,14 +31,71 @@ pub struct BaseMessageFields {
 }
 
 pub trait BaseMessage {
-    fn content(&self) -> &str;
+    fn content(&self) -> Cow<'_, str>;
+    fn message_content(&self) -> &MessageContent;
     fn message_type(&self) -> &MessageType;
     fn role(&self) -> &str;
     fn name(&self) -> Option<&str>;
     fn is_example(&self) -> bool;
-    fn additional_kwargs(&self) -> &std::collections::HashMap<String, String>;
-    fn response_metadata(&self) -> &std::collections::HashMap<String, String>;
+    fn additional_kwargs(&self) -> &std::collections::HashMap<String, Value>;
+    fn response_metadata(&self) -> &std::collections::HashMap<String, Value>;
+    fn additional_kwargs_mut(&mut self) -> &mut std::collections::HashMap<String, Value>;
+    fn response_metadata_mut(&mut self) -> &mut std::collections::HashMap<String, Value>;
     fn id(&self) -> Option<&str>;
+
+    /// Deserializes the `additional_kwargs` entry for `key`, if present.
+    fn get_additional_kwarg<'a, T: Deserialize<'a>>(
+        &'a self,
+        key: &str,
+    ) -> Result<Option<T>, serde_json::Error>
+    where
+        Self: Sized,
+    {
+        self.additional_kwargs()
+            .get(key)
+            .map(T::deserialize)
+            .transpose()
+    }
+
+    fn insert_additional_kwarg<T: Serialize>(
+        &mut self,
+        key: impl Into<String>,
+        value: T,
+    ) -> Result<(), serde_json::Error>
+    where
+        Self: Sized,
+    {
+        let value = serde_json::to_value(value)?;
+        self.additional_kwargs_mut().insert(key.into(), value);
+        Ok(())
+    }
+
+    /// Deserializes the `response_metadata` entry for `key`, if present.
+    fn get_response_metadata<'a, T: Deserialize<'a>>(
+        &'a self,
+        key: &str,
+    ) -> Result<Option<T>, serde_json::Error>
+    where
+        Self: Sized,
+    {
+        self.response_metadata()
+            .get(key)
+            .map(T::deserialize)
+            .transpose()
+    }
+
+    fn insert_response_metadata<T: Serialize>(
+        &mut self,
+        key: impl Into<String>,
+        value: T,
+    ) -> Result<(), serde_json::Error>
+    where
+        Self: Sized,
+    {
+        let value = serde_json::to_value(value)?;
+        self.response_metadata_mut().insert(key.into(), value);
+        Ok(())
+    }
 }
 
 impl Debug for dyn BaseMessage {
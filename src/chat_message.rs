@@ -1,13 +1,27 @@
 use crate::prelude::*;
 use derive_base_message::BaseMessage;
 
-#[derive(BaseMessage, Debug, Serialize, Deserialize)]
+#[derive(BaseMessage, Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatMessage {
     role: String,
     #[serde(flatten)]
     base: BaseMessageFields,
 }
 
+impl ChatMessage {
+    pub fn new_with_base(role: String, base: BaseMessageFields) -> Self {
+        ChatMessage { role, base }
+    }
+
+    pub(crate) fn base_mut(&mut self) -> &mut BaseMessageFields {
+        &mut self.base
+    }
+
+    pub(crate) fn into_base(self) -> BaseMessageFields {
+        self.base
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,10 +45,10 @@ mod tests {
     #[test]
     fn test_chat_message_serialization_with_values() {
         let mut additional_kwargs = HashMap::new();
-        additional_kwargs.insert("key1".to_string(), "value1".to_string());
+        additional_kwargs.insert("key1".to_string(), "value1".into());
 
         let mut response_metadata = HashMap::new();
-        response_metadata.insert("meta_key".to_string(), "meta_value".to_string());
+        response_metadata.insert("meta_key".to_string(), "meta_value".into());
 
         let mut chat_message = ChatMessage::new("Test message", "User".to_string());
 
@@ -92,7 +106,7 @@ mod tests {
     #[test]
     fn test_chat_message_serialization_with_partial_values() {
         let mut additional_kwargs = HashMap::new();
-        additional_kwargs.insert("key2".to_string(), "value2".to_string());
+        additional_kwargs.insert("key2".to_string(), "value2".into());
 
         let mut chat_message = ChatMessage::new("Partial message", "User".to_string());
 
@@ -0,0 +1,569 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{resolve_chat_role, ConversionError};
+use crate::tool_message::ToolStatus;
+use crate::usage_metadata::{InputTokenDetails, UsageMetadata};
+use crate::{
+    AiMessage, BaseMessage, ContentBlock, MediaSource, MessageContent, MessageEnum, ToolCall,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AnthropicRequest {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub system: Option<String>,
+    pub messages: Vec<AnthropicMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AnthropicMessage {
+    pub role: AnthropicRole,
+    pub content: AnthropicContent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnthropicRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum AnthropicContent {
+    Text(String),
+    Blocks(Vec<AnthropicContentBlock>),
+}
+
+/// `additional_kwargs` key holding the response blocks that have no message
+/// equivalent, such as `thinking`, each with its position in the response.
+pub const CONTENT_BLOCKS_KEY: &str = "anthropic_content_blocks";
+
+/// A block kept under [`CONTENT_BLOCKS_KEY`] and the position it had among
+/// the response's content blocks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct StashedBlock {
+    index: usize,
+    block: AnthropicContentBlock,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        source: AnthropicSource,
+    },
+    Document {
+        source: AnthropicSource,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        title: Option<String>,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: AnthropicContent,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        is_error: Option<bool>,
+    },
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
+    /// Any other block, such as `server_tool_use`, kept as received.
+    #[serde(untagged)]
+    Other(Value),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+    File { file_id: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AnthropicResponse {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    pub content: Vec<AnthropicContentBlock>,
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub stop_sequence: Option<String>,
+    #[serde(default)]
+    pub usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AnthropicUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u64>,
+}
+
+impl AnthropicContent {
+    fn into_blocks(self) -> Vec<AnthropicContentBlock> {
+        match self {
+            AnthropicContent::Text(text) => vec![AnthropicContentBlock::Text { text }],
+            AnthropicContent::Blocks(blocks) => blocks,
+        }
+    }
+}
+
+impl From<&AnthropicUsage> for UsageMetadata {
+    /// Anthropic reports cached prompt tokens separately from `input_tokens`,
+    /// so they are folded back into the input total here.
+    fn from(usage: &AnthropicUsage) -> Self {
+        let cache_creation = usage.cache_creation_input_tokens.unwrap_or(0);
+        let cache_read = usage.cache_read_input_tokens.unwrap_or(0);
+        let mut metadata = UsageMetadata::new(
+            usage.input_tokens + cache_creation + cache_read,
+            usage.output_tokens,
+        );
+        if usage.cache_creation_input_tokens.is_some() || usage.cache_read_input_tokens.is_some() {
+            metadata.input_token_details = Some(InputTokenDetails {
+                audio: None,
+                cache_creation: usage.cache_creation_input_tokens,
+                cache_read: usage.cache_read_input_tokens,
+            });
+        }
+        metadata
+    }
+}
+
+/// Builds an Anthropic Messages request body. Leading system messages are
+/// hoisted into `system`, tool results are sent as `tool_result` blocks in a
+/// user turn and consecutive turns with the same role are merged.
+pub fn to_anthropic_request(messages: &[MessageEnum]) -> Result<AnthropicRequest, ConversionError> {
+    let mut system = Vec::new();
+    let mut turns: Vec<AnthropicMessage> = Vec::new();
+
+    for (index, message) in messages.iter().enumerate() {
+        let message = resolve_chat_role(index, message)?;
+        let (role, blocks) = match message.as_ref() {
+            MessageEnum::System(message) => {
+                if !turns.is_empty() {
+                    return Err(ConversionError::InvalidSequence {
+                        index,
+                        reason: "system messages must come before any other message".to_string(),
+                    });
+                }
+                system.push(text_only(index, message.message_content())?);
+                continue;
+            }
+            MessageEnum::Human(message) => (
+                AnthropicRole::User,
+                to_anthropic_blocks(index, message.message_content())?,
+            ),
+            MessageEnum::Ai(message) => {
+                if !message.invalid_tool_calls().is_empty() {
+                    return Err(ConversionError::UnsupportedContent {
+                        index,
+                        reason: "invalid tool calls cannot be sent to Anthropic".to_string(),
+                    });
+                }
+                let mut blocks = to_anthropic_blocks(index, message.message_content())?;
+                for tool_call in message.tool_calls() {
+                    blocks.push(AnthropicContentBlock::ToolUse {
+                        id: tool_call
+                            .id()
+                            .ok_or(ConversionError::MissingField {
+                                index,
+                                field: "tool_calls.id",
+                            })?
+                            .to_string(),
+                        name: tool_call.name().to_string(),
+                        input: tool_call.args().clone(),
+                    });
+                }
+                let mut stashed: Vec<StashedBlock> = message
+                    .get_additional_kwarg(CONTENT_BLOCKS_KEY)?
+                    .unwrap_or_default();
+                stashed.sort_by_key(|stashed| stashed.index);
+                for StashedBlock {
+                    index: position,
+                    block,
+                } in stashed
+                {
+                    blocks.insert(position.min(blocks.len()), block);
+                }
+                if blocks.is_empty() {
+                    return Err(ConversionError::UnsupportedContent {
+                        index,
+                        reason: "Anthropic does not accept an assistant turn without content"
+                            .to_string(),
+                    });
+                }
+                (AnthropicRole::Assistant, blocks)
+            }
+            MessageEnum::Tool(message) => (
+                AnthropicRole::User,
+                vec![AnthropicContentBlock::ToolResult {
+                    tool_use_id: message.tool_call_id().to_string(),
+                    content: AnthropicContent::Blocks(to_anthropic_blocks(
+                        index,
+                        message.message_content(),
+                    )?),
+                    is_error: (*message.status() == ToolStatus::Error).then_some(true),
+                }],
+            ),
+            message @ (MessageEnum::Chat(_) | MessageEnum::Remove(_)) => {
+                return Err(ConversionError::UnsupportedRole {
+                    index,
+                    role: message.role().to_string(),
+                })
+            }
+        };
+
+        match turns.last_mut() {
+            Some(last) if last.role == role => {
+                let content =
+                    std::mem::replace(&mut last.content, AnthropicContent::Blocks(Vec::new()));
+                let mut merged = content.into_blocks();
+                merged.extend(blocks);
+                last.content = AnthropicContent::Blocks(merged);
+            }
+            _ => turns.push(AnthropicMessage {
+                role,
+                content: AnthropicContent::Blocks(blocks),
+            }),
+        }
+    }
+
+    Ok(AnthropicRequest {
+        system: (!system.is_empty()).then(|| system.join("\n\n")),
+        messages: turns,
+    })
+}
+
+/// Converts the content blocks of a Messages API response into an
+/// `AiMessage`, mapping `tool_use` blocks to tool calls. Thinking and other
+/// provider-specific blocks are kept under [`CONTENT_BLOCKS_KEY`] and sent
+/// back in their original position by `to_anthropic_request`, which
+/// Anthropic requires for tool use with extended thinking.
+pub fn from_anthropic_response(response: &AnthropicResponse) -> Result<AiMessage, ConversionError> {
+    let mut blocks = Vec::new();
+    let mut tool_calls = Vec::new();
+    let mut provider_blocks = Vec::new();
+
+    for (position, block) in response.content.iter().enumerate() {
+        match block {
+            AnthropicContentBlock::Text { text } => blocks.push(ContentBlock::text(text.as_str())),
+            AnthropicContentBlock::ToolUse { id, name, input } => tool_calls.push(ToolCall::new(
+                name.as_str(),
+                input.clone(),
+                Some(id.clone()),
+            )),
+            block @ (AnthropicContentBlock::Thinking { .. }
+            | AnthropicContentBlock::RedactedThinking { .. }
+            | AnthropicContentBlock::Other(_)) => provider_blocks.push(StashedBlock {
+                index: position,
+                block: block.clone(),
+            }),
+            other => {
+                return Err(ConversionError::UnsupportedContent {
+                    index: 0,
+                    reason: format!("unexpected block in response: {:?}", other),
+                })
+            }
+        }
+    }
+
+    let content = match blocks.as_slice() {
+        [] => MessageContent::default(),
+        [ContentBlock::Text { text }] => MessageContent::Text(text.clone()),
+        _ => MessageContent::Blocks(blocks),
+    };
+
+    let mut message = AiMessage::new_with_tool_calls(content, tool_calls);
+    message.set_id(response.id.clone());
+    if !provider_blocks.is_empty() {
+        message.insert_additional_kwarg(CONTENT_BLOCKS_KEY, provider_blocks)?;
+    }
+    let metadata = message.response_metadata_mut();
+    if let Some(model) = &response.model {
+        metadata.insert("model_name".to_string(), model.as_str().into());
+    }
+    if let Some(stop_reason) = &response.stop_reason {
+        metadata.insert("stop_reason".to_string(), stop_reason.as_str().into());
+    }
+    if let Some(stop_sequence) = &response.stop_sequence {
+        metadata.insert("stop_sequence".to_string(), stop_sequence.as_str().into());
+    }
+    message.set_usage_metadata(response.usage.as_ref().map(UsageMetadata::from));
+
+    Ok(message)
+}
+
+fn text_only(index: usize, content: &MessageContent) -> Result<String, ConversionError> {
+    match content
+        .blocks()
+        .iter()
+        .find(|block| block.as_text().is_none())
+    {
+        Some(block) => Err(ConversionError::UnsupportedContent {
+            index,
+            reason: format!("system prompt only accepts text content, found {:?}", block),
+        }),
+        None => Ok(content.text().into_owned()),
+    }
+}
+
+fn to_anthropic_blocks(
+    index: usize,
+    content: &MessageContent,
+) -> Result<Vec<AnthropicContentBlock>, ConversionError> {
+    content
+        .blocks()
+        .iter()
+        .filter(|block| block.as_text() != Some(""))
+        .map(|block| to_anthropic_block(index, block))
+        .collect()
+}
+
+fn to_anthropic_block(
+    index: usize,
+    block: &ContentBlock,
+) -> Result<AnthropicContentBlock, ConversionError> {
+    let block = match block {
+        ContentBlock::Text { text } => AnthropicContentBlock::Text { text: text.clone() },
+        ContentBlock::Image { source, .. } => AnthropicContentBlock::Image {
+            source: to_anthropic_source(source),
+        },
+        ContentBlock::File { source, filename } => AnthropicContentBlock::Document {
+            source: to_anthropic_source(source),
+            title: filename.clone(),
+        },
+        ContentBlock::Audio { .. } => {
+            return Err(ConversionError::UnsupportedContent {
+                index,
+                reason: "Anthropic does not accept audio content".to_string(),
+            })
+        }
+    };
+    Ok(block)
+}
+
+fn to_anthropic_source(source: &MediaSource) -> AnthropicSource {
+    match source {
+        MediaSource::Base64 { mime_type, data } => AnthropicSource::Base64 {
+            media_type: mime_type.clone(),
+            data: data.clone(),
+        },
+        MediaSource::Url { url } => AnthropicSource::Url { url: url.clone() },
+        MediaSource::Id { id } => AnthropicSource::File {
+            file_id: id.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HumanMessage, SystemMessage, ToolMessage};
+    use serde_json::json;
+
+    #[test]
+    fn test_system_messages_are_hoisted() {
+        let messages = vec![
+            MessageEnum::System(SystemMessage::new("You are terse.")),
+            MessageEnum::System(SystemMessage::new("Answer in French.")),
+            MessageEnum::Human(HumanMessage::new("Hello")),
+        ];
+
+        let request = to_anthropic_request(&messages).unwrap();
+        assert_eq!(
+            request.system.as_deref(),
+            Some("You are terse.\n\nAnswer in French.")
+        );
+        assert_eq!(request.messages.len(), 1);
+    }
+
+    #[test]
+    fn test_late_system_message_is_rejected() {
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("Hello")),
+            MessageEnum::System(SystemMessage::new("You are terse.")),
+        ];
+
+        let err = to_anthropic_request(&messages).unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::InvalidSequence { index: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_consecutive_turns_are_merged() {
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("First")),
+            MessageEnum::Human(HumanMessage::new("Second")),
+        ];
+
+        let request = to_anthropic_request(&messages).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "messages": [{
+                    "role": "user",
+                    "content": [
+                        {"type": "text", "text": "First"},
+                        {"type": "text", "text": "Second"}
+                    ]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_tool_error_maps_to_is_error() {
+        let ai = AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "get_weather",
+                json!({"city": "Paris"}),
+                Some("toolu_1".to_string()),
+            )],
+        );
+        let tool = ToolMessage::new(
+            "City not found",
+            "toolu_1".to_string(),
+            None,
+            ToolStatus::Error,
+        );
+
+        let request = to_anthropic_request(&[ai.into(), tool.into()]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.messages).unwrap(),
+            json!([
+                {
+                    "role": "assistant",
+                    "content": [{
+                        "type": "tool_use",
+                        "id": "toolu_1",
+                        "name": "get_weather",
+                        "input": {"city": "Paris"}
+                    }]
+                },
+                {
+                    "role": "user",
+                    "content": [{
+                        "type": "tool_result",
+                        "tool_use_id": "toolu_1",
+                        "content": [{"type": "text", "text": "City not found"}],
+                        "is_error": true
+                    }]
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_audio_is_rejected() {
+        let human = HumanMessage::new(vec![ContentBlock::audio_base64("audio/wav", "UklGRg==")]);
+
+        let err = to_anthropic_request(&[human.into()]).unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::UnsupportedContent { index: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn test_usage_includes_cached_tokens() {
+        let usage = AnthropicUsage {
+            input_tokens: 10,
+            output_tokens: 5,
+            cache_creation_input_tokens: Some(20),
+            cache_read_input_tokens: Some(30),
+        };
+
+        let metadata = UsageMetadata::from(&usage);
+        assert_eq!(metadata.input_tokens, 60);
+        assert_eq!(metadata.total_tokens, 65);
+        assert_eq!(metadata.cached_input_tokens(), 30);
+    }
+
+    #[test]
+    fn test_unknown_blocks_are_preserved() {
+        let response: AnthropicResponse = serde_json::from_value(json!({
+            "content": [
+                {"type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": {"query": "weather"}},
+                {"type": "text", "text": "It is sunny."}
+            ]
+        }))
+        .unwrap();
+
+        let ai = from_anthropic_response(&response).unwrap();
+        assert_eq!(ai.content(), "It is sunny.");
+
+        let request = to_anthropic_request(&[
+            MessageEnum::Human(HumanMessage::new("Weather?")),
+            MessageEnum::Ai(ai),
+        ])
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.messages[1].content).unwrap(),
+            json!([
+                {"type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": {"query": "weather"}},
+                {"type": "text", "text": "It is sunny."}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_stashed_blocks_keep_their_position() {
+        let content = json!([
+            {"type": "text", "text": "Let me search."},
+            {"type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": {"query": "weather"}},
+            {"type": "web_search_tool_result", "tool_use_id": "srvtoolu_1", "content": []},
+            {"type": "text", "text": "It is sunny."},
+            {"type": "redacted_thinking", "data": "opaque"},
+            {"type": "tool_use", "id": "toolu_1", "name": "forecast", "input": {}}
+        ]);
+        let response: AnthropicResponse =
+            serde_json::from_value(json!({ "content": content })).unwrap();
+
+        let ai = from_anthropic_response(&response).unwrap();
+        assert_eq!(ai.content(), "Let me search.It is sunny.");
+
+        let request = to_anthropic_request(&[
+            MessageEnum::Human(HumanMessage::new("Weather?")),
+            MessageEnum::Ai(ai),
+        ])
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.messages[1].content).unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn test_empty_assistant_turn_is_rejected() {
+        let err = to_anthropic_request(&[
+            MessageEnum::Human(HumanMessage::new("Hi")),
+            MessageEnum::Ai(AiMessage::new("")),
+        ])
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::UnsupportedContent { index: 1, .. }
+        ));
+    }
+}
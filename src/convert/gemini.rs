@@ -0,0 +1,905 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{resolve_chat_role, ConversionError};
+use crate::tool_message::ToolStatus;
+use crate::usage_metadata::{InputTokenDetails, OutputTokenDetails, UsageMetadata};
+use crate::{
+    AiMessage, BaseMessage, ContentBlock, HumanMessage, MediaSource, MessageContent, MessageEnum,
+    SystemMessage, ToolCall, ToolMessage,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiRequest {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub system_instruction: Option<GeminiContent>,
+    pub contents: Vec<GeminiContent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GeminiContent {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub role: Option<GeminiRole>,
+    pub parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GeminiRole {
+    User,
+    Model,
+}
+
+/// `additional_kwargs` key holding the `thoughtSignature` of each function
+/// call in a model turn, keyed by tool call id.
+pub const THOUGHT_SIGNATURES_KEY: &str = "gemini_thought_signatures";
+/// `additional_kwargs` key holding the signed text parts of a model turn, in
+/// order, as `{"text": ..., "signature": ...}` objects.
+pub const TEXT_THOUGHT_SIGNATURES_KEY: &str = "gemini_text_thought_signatures";
+/// `additional_kwargs` key listing tool call ids that were generated because
+/// Gemini sent the function call without one. They are not sent back.
+pub const GENERATED_CALL_IDS_KEY: &str = "gemini_generated_call_ids";
+/// `additional_kwargs` key holding the thought summaries of a model turn.
+pub const REASONING_CONTENT_KEY: &str = "reasoning_content";
+
+/// A single part of a Gemini turn. Exactly one of the data fields is set;
+/// `thought` and `thoughtSignature` may accompany any of them.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiPart {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub inline_data: Option<GeminiBlob>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub file_data: Option<GeminiFileData>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub function_call: Option<GeminiFunctionCall>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub function_response: Option<GeminiFunctionResponse>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub thought: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub thought_signature: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct TextSignature {
+    text: String,
+    signature: String,
+}
+
+impl GeminiPart {
+    pub fn text(text: impl Into<String>) -> Self {
+        GeminiPart {
+            text: Some(text.into()),
+            ..GeminiPart::default()
+        }
+    }
+
+    pub fn is_thought(&self) -> bool {
+        self.thought == Some(true)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiBlob {
+    pub mime_type: String,
+    pub data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiFileData {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub mime_type: Option<String>,
+    pub file_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GeminiFunctionCall {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GeminiFunctionResponse {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub response: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiResponse {
+    pub candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    pub usage_metadata: Option<GeminiUsage>,
+    #[serde(default)]
+    pub model_version: Option<String>,
+    #[serde(default)]
+    pub response_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiCandidate {
+    pub content: GeminiContent,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiUsage {
+    #[serde(default)]
+    pub prompt_token_count: u64,
+    #[serde(default)]
+    pub candidates_token_count: u64,
+    #[serde(default)]
+    pub total_token_count: u64,
+    #[serde(default)]
+    pub cached_content_token_count: Option<u64>,
+    #[serde(default)]
+    pub thoughts_token_count: Option<u64>,
+}
+
+impl From<&GeminiUsage> for UsageMetadata {
+    /// Thinking tokens are billed as output but reported separately by Gemini.
+    fn from(usage: &GeminiUsage) -> Self {
+        UsageMetadata {
+            input_tokens: usage.prompt_token_count,
+            output_tokens: usage.candidates_token_count + usage.thoughts_token_count.unwrap_or(0),
+            total_tokens: usage.total_token_count,
+            input_token_details: usage
+                .cached_content_token_count
+                .map(|cached| InputTokenDetails {
+                    audio: None,
+                    cache_creation: None,
+                    cache_read: Some(cached),
+                }),
+            output_token_details: usage
+                .thoughts_token_count
+                .map(|thoughts| OutputTokenDetails {
+                    audio: None,
+                    reasoning: Some(thoughts),
+                }),
+        }
+    }
+}
+
+/// Builds a Gemini `generateContent` request body. Leading system messages
+/// become `systemInstruction`, tool results become `functionResponse` parts
+/// in a user turn and consecutive turns with the same role are merged.
+pub fn to_gemini_request(messages: &[MessageEnum]) -> Result<GeminiRequest, ConversionError> {
+    let mut system_parts = Vec::new();
+    let mut contents: Vec<GeminiContent> = Vec::new();
+    let mut pending_calls: Vec<ToolCall> = Vec::new();
+    let mut generated_ids: HashSet<String> = HashSet::new();
+
+    for (index, message) in messages.iter().enumerate() {
+        let message = resolve_chat_role(index, message)?;
+        let (role, parts) = match message.as_ref() {
+            MessageEnum::System(message) => {
+                if !contents.is_empty() {
+                    return Err(ConversionError::InvalidSequence {
+                        index,
+                        reason: "system messages must come before any other message".to_string(),
+                    });
+                }
+                system_parts.extend(to_gemini_parts(index, message.message_content())?);
+                continue;
+            }
+            MessageEnum::Human(message) => {
+                pending_calls.clear();
+                (
+                    GeminiRole::User,
+                    to_gemini_parts(index, message.message_content())?,
+                )
+            }
+            MessageEnum::Ai(message) => {
+                if !message.invalid_tool_calls().is_empty() {
+                    return Err(ConversionError::UnsupportedContent {
+                        index,
+                        reason: "invalid tool calls cannot be sent to Gemini".to_string(),
+                    });
+                }
+                pending_calls = message.tool_calls().to_vec();
+                generated_ids = message
+                    .get_additional_kwarg(GENERATED_CALL_IDS_KEY)?
+                    .unwrap_or_default();
+                (
+                    GeminiRole::Model,
+                    to_gemini_model_parts(index, message, &generated_ids)?,
+                )
+            }
+            MessageEnum::Tool(message) => {
+                let tool_call = pending_calls
+                    .iter()
+                    .find(|tool_call| tool_call.id() == Some(message.tool_call_id()))
+                    .ok_or_else(|| ConversionError::InvalidSequence {
+                        index,
+                        reason: format!(
+                            "tool message {} does not answer a function call from the preceding model turn",
+                            message.tool_call_id()
+                        ),
+                    })?;
+                let part = GeminiPart {
+                    function_response: Some(GeminiFunctionResponse {
+                        id: (!generated_ids.contains(message.tool_call_id()))
+                            .then(|| message.tool_call_id().to_string()),
+                        name: message.name().unwrap_or(tool_call.name()).to_string(),
+                        response: to_gemini_response(message),
+                    }),
+                    ..GeminiPart::default()
+                };
+                (GeminiRole::User, vec![part])
+            }
+            message @ (MessageEnum::Chat(_) | MessageEnum::Remove(_)) => {
+                return Err(ConversionError::UnsupportedRole {
+                    index,
+                    role: message.role().to_string(),
+                })
+            }
+        };
+
+        match contents.last_mut() {
+            Some(last) if last.role == Some(role) => last.parts.extend(parts),
+            _ => contents.push(GeminiContent {
+                role: Some(role),
+                parts,
+            }),
+        }
+    }
+
+    if contents.is_empty() {
+        return Err(ConversionError::InvalidSequence {
+            index: messages.len(),
+            reason: "Gemini requires at least one user or model message".to_string(),
+        });
+    }
+
+    Ok(GeminiRequest {
+        system_instruction: (!system_parts.is_empty()).then_some(GeminiContent {
+            role: None,
+            parts: system_parts,
+        }),
+        contents,
+    })
+}
+
+/// Converts a Gemini request body back into messages. Function calls without
+/// an id are given a unique one so that their responses can be paired by
+/// name; such ids are listed under [`GENERATED_CALL_IDS_KEY`].
+pub fn from_gemini_request(request: &GeminiRequest) -> Result<Vec<MessageEnum>, ConversionError> {
+    let mut messages = Vec::new();
+
+    if let Some(system) = &request.system_instruction {
+        messages.push(MessageEnum::System(SystemMessage::new(from_gemini_parts(
+            0,
+            &system.parts,
+        )?)));
+    }
+
+    let mut pending_calls: Vec<(String, String)> = Vec::new();
+    for (index, content) in request.contents.iter().enumerate() {
+        match content.role {
+            Some(GeminiRole::Model) => {
+                let ai = from_gemini_model_content(index, content, &generate_call_id_prefix())?;
+                pending_calls = ai
+                    .tool_calls()
+                    .iter()
+                    .filter_map(|tool_call| {
+                        Some((tool_call.id()?.to_string(), tool_call.name().to_string()))
+                    })
+                    .collect();
+                messages.push(MessageEnum::Ai(ai));
+            }
+            Some(GeminiRole::User) | None => {
+                let mut parts = Vec::new();
+                for part in &content.parts {
+                    match &part.function_response {
+                        Some(response) => {
+                            if !parts.is_empty() {
+                                messages.push(MessageEnum::Human(HumanMessage::new(
+                                    from_gemini_parts(index, &parts)?,
+                                )));
+                                parts.clear();
+                            }
+                            messages.push(MessageEnum::Tool(from_gemini_function_response(
+                                index,
+                                response,
+                                &mut pending_calls,
+                            )?));
+                        }
+                        None => parts.push(part.clone()),
+                    }
+                }
+                if !parts.is_empty() {
+                    messages.push(MessageEnum::Human(HumanMessage::new(from_gemini_parts(
+                        index, &parts,
+                    )?)));
+                }
+            }
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Converts the first candidate of a `generateContent` response into an
+/// `AiMessage`. Function calls without an id are given one derived from the
+/// response id.
+pub fn from_gemini_response(response: &GeminiResponse) -> Result<AiMessage, ConversionError> {
+    let candidate = response.candidates.first().ok_or_else(|| {
+        ConversionError::InvalidPayload("Gemini response has no candidates".to_string())
+    })?;
+
+    let id_prefix = match &response.response_id {
+        Some(response_id) => format!("call_{}", response_id),
+        None => generate_call_id_prefix(),
+    };
+    let mut message = from_gemini_model_content(0, &candidate.content, &id_prefix)?;
+    message.set_id(response.response_id.clone());
+    let metadata = message.response_metadata_mut();
+    if let Some(model_version) = &response.model_version {
+        metadata.insert("model_name".to_string(), model_version.as_str().into());
+    }
+    if let Some(finish_reason) = &candidate.finish_reason {
+        metadata.insert("finish_reason".to_string(), finish_reason.as_str().into());
+    }
+    message.set_usage_metadata(response.usage_metadata.as_ref().map(UsageMetadata::from));
+
+    Ok(message)
+}
+
+fn to_gemini_parts(
+    index: usize,
+    content: &MessageContent,
+) -> Result<Vec<GeminiPart>, ConversionError> {
+    content
+        .blocks()
+        .iter()
+        .filter(|block| block.as_text() != Some(""))
+        .map(|block| to_gemini_part(index, block))
+        .collect()
+}
+
+/// Builds the parts of a model turn, reattaching the thought signatures that
+/// `from_gemini_response` kept in `additional_kwargs`. Signed text is found
+/// by its content, so a text block that `merge_message_runs` joined from
+/// several signed parts is split back into those parts.
+fn to_gemini_model_parts(
+    index: usize,
+    message: &AiMessage,
+    generated_ids: &HashSet<String>,
+) -> Result<Vec<GeminiPart>, ConversionError> {
+    let text_signatures: Vec<TextSignature> = message
+        .get_additional_kwarg(TEXT_THOUGHT_SIGNATURES_KEY)?
+        .unwrap_or_default();
+    let call_signatures: HashMap<String, String> = message
+        .get_additional_kwarg(THOUGHT_SIGNATURES_KEY)?
+        .unwrap_or_default();
+
+    let mut parts = Vec::new();
+    let mut signatures = text_signatures.iter().peekable();
+    for block in message.message_content().blocks().iter() {
+        let Some(mut rest) = block.as_text() else {
+            parts.push(to_gemini_part(index, block)?);
+            continue;
+        };
+        while let Some(signed) = signatures.peek() {
+            let Some(start) = rest.find(signed.text.as_str()) else {
+                break;
+            };
+            if start > 0 {
+                parts.push(GeminiPart::text(&rest[..start]));
+            }
+            parts.push(GeminiPart {
+                thought_signature: Some(signed.signature.clone()),
+                ..GeminiPart::text(signed.text.as_str())
+            });
+            rest = &rest[start + signed.text.len()..];
+            signatures.next();
+        }
+        if !rest.is_empty() {
+            parts.push(GeminiPart::text(rest));
+        }
+    }
+    // Signed empty parts leave no block behind in the content.
+    parts.extend(
+        signatures
+            .filter(|signed| signed.text.is_empty())
+            .map(|signed| GeminiPart {
+                thought_signature: Some(signed.signature.clone()),
+                ..GeminiPart::text("")
+            }),
+    );
+    parts.extend(message.tool_calls().iter().map(|tool_call| {
+        GeminiPart {
+            function_call: Some(GeminiFunctionCall {
+                id: tool_call
+                    .id
+                    .clone()
+                    .filter(|id| !generated_ids.contains(id)),
+                name: tool_call.name.clone(),
+                args: tool_call.args.clone(),
+            }),
+            thought_signature: tool_call
+                .id()
+                .and_then(|id| call_signatures.get(id))
+                .cloned(),
+            ..GeminiPart::default()
+        }
+    }));
+    Ok(parts)
+}
+
+fn to_gemini_part(index: usize, block: &ContentBlock) -> Result<GeminiPart, ConversionError> {
+    let source = match block {
+        ContentBlock::Text { text } => return Ok(GeminiPart::text(text.as_str())),
+        ContentBlock::Image { source, .. }
+        | ContentBlock::Audio { source }
+        | ContentBlock::File { source, .. } => source,
+    };
+
+    match source {
+        MediaSource::Base64 { mime_type, data } => Ok(GeminiPart {
+            inline_data: Some(GeminiBlob {
+                mime_type: mime_type.clone(),
+                data: data.clone(),
+            }),
+            ..GeminiPart::default()
+        }),
+        MediaSource::Url { url } => Ok(GeminiPart {
+            file_data: Some(GeminiFileData {
+                mime_type: None,
+                file_uri: url.clone(),
+            }),
+            ..GeminiPart::default()
+        }),
+        MediaSource::Id { id } => Err(ConversionError::UnsupportedContent {
+            index,
+            reason: format!(
+                "Gemini cannot reference file id {}; use its URI instead",
+                id
+            ),
+        }),
+    }
+}
+
+/// Gemini expects the function response to be a JSON object. Text results
+/// that are not objects are wrapped as `{"output": ...}`, or `{"error": ...}`
+/// when the tool failed.
+fn to_gemini_response(message: &ToolMessage) -> Value {
+    let text = message.content();
+    if *message.status() == ToolStatus::Success {
+        if let Ok(value @ Value::Object(_)) = serde_json::from_str::<Value>(&text) {
+            return value;
+        }
+    }
+
+    let key = match message.status() {
+        ToolStatus::Success => "output",
+        ToolStatus::Error => "error",
+    };
+    let mut response = Map::new();
+    response.insert(key.to_string(), Value::String(text.into_owned()));
+    Value::Object(response)
+}
+
+/// Converts a model turn into an `AiMessage`. Thought summaries and thought
+/// signatures are kept in `additional_kwargs` so that the turn can be sent
+/// back unchanged, as Gemini requires when thinking is combined with
+/// function calling.
+fn from_gemini_model_content(
+    index: usize,
+    content: &GeminiContent,
+    id_prefix: &str,
+) -> Result<AiMessage, ConversionError> {
+    let mut parts = Vec::new();
+    let mut text_signatures = Vec::new();
+    let mut tool_calls = Vec::new();
+    let mut call_signatures = HashMap::new();
+    let mut generated_ids = Vec::new();
+    let mut thoughts = Vec::new();
+
+    for (position, part) in content.parts.iter().enumerate() {
+        if part.function_response.is_some() {
+            return Err(ConversionError::InvalidSequence {
+                index,
+                reason: "function responses must be sent in a user turn".to_string(),
+            });
+        }
+        if let Some(call) = &part.function_call {
+            let id = match &call.id {
+                Some(id) => id.clone(),
+                None => {
+                    let id = format!("{}_{}", id_prefix, position);
+                    generated_ids.push(id.clone());
+                    id
+                }
+            };
+            if let Some(signature) = &part.thought_signature {
+                call_signatures.insert(id.clone(), signature.clone());
+            }
+            tool_calls.push(ToolCall::new(
+                call.name.as_str(),
+                match &call.args {
+                    Value::Null => Value::Object(Map::new()),
+                    args => args.clone(),
+                },
+                Some(id),
+            ));
+        } else if part.is_thought() {
+            thoughts.extend(part.text.clone());
+        } else {
+            if let (Some(text), Some(signature)) = (&part.text, &part.thought_signature) {
+                text_signatures.push(TextSignature {
+                    text: text.clone(),
+                    signature: signature.clone(),
+                });
+            }
+            parts.push(part.clone());
+        }
+    }
+
+    let mut message = AiMessage::new_with_tool_calls(from_gemini_parts(index, &parts)?, tool_calls);
+    if !call_signatures.is_empty() {
+        message.insert_additional_kwarg(THOUGHT_SIGNATURES_KEY, call_signatures)?;
+    }
+    if !text_signatures.is_empty() {
+        message.insert_additional_kwarg(TEXT_THOUGHT_SIGNATURES_KEY, text_signatures)?;
+    }
+    if !generated_ids.is_empty() {
+        message.insert_additional_kwarg(GENERATED_CALL_IDS_KEY, generated_ids)?;
+    }
+    if !thoughts.is_empty() {
+        message.insert_additional_kwarg(REASONING_CONTENT_KEY, thoughts.join("\n\n"))?;
+    }
+    Ok(message)
+}
+
+fn from_gemini_function_response(
+    index: usize,
+    response: &GeminiFunctionResponse,
+    pending_calls: &mut Vec<(String, String)>,
+) -> Result<ToolMessage, ConversionError> {
+    let position = pending_calls
+        .iter()
+        .position(|(id, name)| match &response.id {
+            Some(response_id) => id == response_id,
+            None => *name == response.name,
+        });
+    let tool_call_id = match (position, &response.id) {
+        (Some(position), _) => pending_calls.remove(position).0,
+        (None, Some(id)) => id.clone(),
+        (None, None) => {
+            return Err(ConversionError::InvalidSequence {
+                index,
+                reason: format!(
+                    "function response {} does not match any preceding function call",
+                    response.name
+                ),
+            })
+        }
+    };
+
+    let (content, status) = match &response.response {
+        Value::Object(map) if map.len() == 1 => match map.iter().next() {
+            Some((key, Value::String(text))) if key == "output" => {
+                (text.clone(), ToolStatus::Success)
+            }
+            Some((key, Value::String(text))) if key == "error" => (text.clone(), ToolStatus::Error),
+            _ => (response.response.to_string(), ToolStatus::Success),
+        },
+        other => (other.to_string(), ToolStatus::Success),
+    };
+
+    let mut message = ToolMessage::new(content, tool_call_id, None, status);
+    message.set_name(Some(response.name.clone()));
+    Ok(message)
+}
+
+/// A prefix for ids of function calls that arrived without one, unique
+/// within the process and unlikely to repeat across processes.
+fn generate_call_id_prefix() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    format!(
+        "call_{:x}_{:x}",
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn from_gemini_parts(
+    index: usize,
+    parts: &[GeminiPart],
+) -> Result<MessageContent, ConversionError> {
+    let blocks = parts
+        .iter()
+        .map(|part| from_gemini_part(index, part))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(match blocks.as_slice() {
+        [] => MessageContent::default(),
+        [ContentBlock::Text { text }] => MessageContent::Text(text.clone()),
+        _ => MessageContent::Blocks(blocks),
+    })
+}
+
+fn from_gemini_part(index: usize, part: &GeminiPart) -> Result<ContentBlock, ConversionError> {
+    if let Some(text) = &part.text {
+        return Ok(ContentBlock::text(text.as_str()));
+    }
+    if let Some(blob) = &part.inline_data {
+        return Ok(media_block(
+            &blob.mime_type,
+            MediaSource::Base64 {
+                mime_type: blob.mime_type.clone(),
+                data: blob.data.clone(),
+            },
+        ));
+    }
+    if let Some(file) = &part.file_data {
+        return Ok(media_block(
+            file.mime_type.as_deref().unwrap_or_default(),
+            MediaSource::Url {
+                url: file.file_uri.clone(),
+            },
+        ));
+    }
+    if part.function_call.is_some() || part.function_response.is_some() {
+        return Err(ConversionError::InvalidSequence {
+            index,
+            reason: "function parts are only valid in model and tool turns".to_string(),
+        });
+    }
+    Err(ConversionError::UnsupportedContent {
+        index,
+        reason: format!("part has no supported data: {:?}", part),
+    })
+}
+
+fn media_block(mime_type: &str, source: MediaSource) -> ContentBlock {
+    if mime_type.starts_with("image/") {
+        ContentBlock::Image {
+            source,
+            detail: None,
+        }
+    } else if mime_type.starts_with("audio/") {
+        ContentBlock::Audio { source }
+    } else {
+        ContentBlock::File {
+            source,
+            filename: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn weather_call() -> AiMessage {
+        AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "get_weather",
+                json!({"city": "Paris"}),
+                Some("call_1".to_string()),
+            )],
+        )
+    }
+
+    #[test]
+    fn test_system_instruction_and_roles() {
+        let messages = vec![
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+            MessageEnum::Human(HumanMessage::new("Hi")),
+            MessageEnum::Ai(AiMessage::new("Hello!")),
+        ];
+
+        let request = to_gemini_request(&messages).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "systemInstruction": {"parts": [{"text": "Be brief."}]},
+                "contents": [
+                    {"role": "user", "parts": [{"text": "Hi"}]},
+                    {"role": "model", "parts": [{"text": "Hello!"}]}
+                ]
+            })
+        );
+        assert_eq!(from_gemini_request(&request).unwrap(), messages);
+    }
+
+    #[test]
+    fn test_tool_message_uses_call_name() {
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("Weather?")),
+            MessageEnum::Ai(weather_call()),
+            MessageEnum::Tool(ToolMessage::new(
+                "Service down",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Error,
+            )),
+        ];
+
+        let request = to_gemini_request(&messages).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.contents[2]).unwrap(),
+            json!({
+                "role": "user",
+                "parts": [{
+                    "functionResponse": {
+                        "id": "call_1",
+                        "name": "get_weather",
+                        "response": {"error": "Service down"}
+                    }
+                }]
+            })
+        );
+
+        let round_trip = from_gemini_request(&request).unwrap();
+        let tool = round_trip[2].as_tool().unwrap();
+        assert_eq!(tool.status(), &ToolStatus::Error);
+        assert_eq!(tool.name(), Some("get_weather"));
+    }
+
+    #[test]
+    fn test_tool_message_without_call_is_rejected() {
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("Weather?")),
+            MessageEnum::Tool(ToolMessage::new(
+                "18 degrees",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+        ];
+
+        let err = to_gemini_request(&messages).unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::InvalidSequence { index: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_system_only_conversation_is_rejected() {
+        let messages = vec![MessageEnum::System(SystemMessage::new("Be brief."))];
+
+        let err = to_gemini_request(&messages).unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::InvalidSequence { index: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_function_calls_without_ids_are_paired_by_name() {
+        let request: GeminiRequest = serde_json::from_value(json!({
+            "contents": [
+                {"role": "user", "parts": [{"text": "Weather?"}]},
+                {"role": "model", "parts": [{"functionCall": {"name": "get_weather", "args": {"city": "Paris"}}}]},
+                {"role": "user", "parts": [{"functionResponse": {"name": "get_weather", "response": {"temperature": 18}}}]}
+            ]
+        }))
+        .unwrap();
+
+        let messages = from_gemini_request(&request).unwrap();
+        let call_id = messages[1].as_ai().unwrap().tool_calls()[0].id().unwrap();
+        let tool = messages[2].as_tool().unwrap();
+        assert_eq!(tool.tool_call_id(), call_id);
+        assert_eq!(tool.content(), "{\"temperature\":18}");
+
+        let round_trip = serde_json::to_value(to_gemini_request(&messages).unwrap()).unwrap();
+        assert_eq!(
+            round_trip["contents"][1]["parts"][0]["functionCall"],
+            json!({"name": "get_weather", "args": {"city": "Paris"}})
+        );
+        assert!(round_trip["contents"][2]["parts"][0]["functionResponse"]
+            .get("id")
+            .is_none());
+    }
+
+    #[test]
+    fn test_parts_with_thought_fields() {
+        let content: GeminiContent = serde_json::from_value(json!({
+            "role": "model",
+            "parts": [
+                {"text": "Planning the answer.", "thought": true},
+                {"text": "It is sunny.", "thoughtSignature": "c2lnLTE="}
+            ]
+        }))
+        .unwrap();
+
+        let ai = from_gemini_model_content(0, &content, "call_test").unwrap();
+        assert_eq!(ai.content(), "It is sunny.");
+        assert_eq!(
+            ai.additional_kwargs()[REASONING_CONTENT_KEY],
+            json!("Planning the answer.")
+        );
+
+        let request = to_gemini_request(&[
+            MessageEnum::Human(HumanMessage::new("Weather?")),
+            MessageEnum::Ai(ai),
+        ])
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.contents[1].parts).unwrap(),
+            json!([{"text": "It is sunny.", "thoughtSignature": "c2lnLTE="}])
+        );
+    }
+
+    fn model_turn(parts: Value) -> AiMessage {
+        let content: GeminiContent =
+            serde_json::from_value(json!({"role": "model", "parts": parts})).unwrap();
+        from_gemini_model_content(0, &content, "call_test").unwrap()
+    }
+
+    fn model_parts(messages: Vec<MessageEnum>) -> Value {
+        let mut conversation = vec![MessageEnum::Human(HumanMessage::new("Weather?"))];
+        conversation.extend(messages);
+        let request = to_gemini_request(&conversation).unwrap();
+        serde_json::to_value(&request.contents[1].parts).unwrap()
+    }
+
+    #[test]
+    fn test_signed_empty_part_round_trip() {
+        let parts = json!([
+            {"functionCall": {"id": "call_1", "name": "get_weather", "args": {"city": "Paris"}}},
+            {"text": "", "thoughtSignature": "c2lnLTI="}
+        ]);
+
+        let ai = model_turn(parts.clone());
+        assert_eq!(ai.content(), "");
+        assert_eq!(
+            model_parts(vec![MessageEnum::Ai(ai)]),
+            json!([
+                {"text": "", "thoughtSignature": "c2lnLTI="},
+                parts[0]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_signatures_survive_merged_turns() {
+        let first = model_turn(json!([{"text": "Let me check.", "thoughtSignature": "c2lnLTE="}]));
+        let second = model_turn(json!([
+            {"inlineData": {"mimeType": "image/png", "data": "iVBORw0KGgo="}},
+            {"text": "Here is the map.", "thoughtSignature": "c2lnLTI="}
+        ]));
+
+        let merged = crate::utils::merge_message_runs(
+            &[MessageEnum::Ai(first), MessageEnum::Ai(second)],
+            " ",
+        );
+        assert_eq!(merged.len(), 1);
+        assert_eq!(
+            model_parts(merged),
+            json!([
+                {"text": "Let me check.", "thoughtSignature": "c2lnLTE="},
+                {"text": " "},
+                {"inlineData": {"mimeType": "image/png", "data": "iVBORw0KGgo="}},
+                {"text": "Here is the map.", "thoughtSignature": "c2lnLTI="}
+            ])
+        );
+    }
+}
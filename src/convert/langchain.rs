@@ -0,0 +1,537 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use super::ConversionError;
+use crate::tool_message::ToolStatus;
+use crate::{
+    AiMessage, BaseMessage, BaseMessageFields, ChatMessage, ContentBlock, HumanMessage,
+    InvalidToolCall, MediaSource, MessageContent, MessageEnum, MessageType, RemoveMessage,
+    SystemMessage, ToolCall, ToolMessage, UsageMetadata,
+};
+
+const LC_NAMESPACE: [&str; 3] = ["langchain", "schema", "messages"];
+
+/// `additional_kwargs` key holding the original LangChain content when it
+/// cannot be represented exactly, for example `image_url` or `tool_use`
+/// parts. It is written back in place of `content` when the message is
+/// dumped again, unless the content has changed in the meantime.
+pub const CONTENT_KEY: &str = "langchain_content";
+/// `additional_kwargs` key holding a `ToolMessage` artifact that is not a
+/// string. It is written back as `artifact` when the message is dumped.
+pub const ARTIFACT_KEY: &str = "langchain_artifact";
+
+/// Field layout shared by `dumpd` kwargs and `messages_to_dict` data.
+#[derive(Deserialize)]
+struct LcMessageData {
+    content: Value,
+    #[serde(default)]
+    additional_kwargs: Option<HashMap<String, Value>>,
+    #[serde(default)]
+    response_metadata: Option<HashMap<String, Value>>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    example: bool,
+
+    #[serde(default)]
+    tool_calls: Vec<ToolCall>,
+    #[serde(default)]
+    invalid_tool_calls: Vec<InvalidToolCall>,
+    #[serde(default)]
+    usage_metadata: Option<UsageMetadata>,
+
+    #[serde(default)]
+    tool_call_id: Option<String>,
+    #[serde(default)]
+    artifact: Option<Value>,
+    #[serde(default)]
+    status: Option<String>,
+
+    #[serde(default)]
+    role: Option<String>,
+}
+
+/// Serializes a message the way LangChain's `dumpd` does:
+/// `{"lc": 1, "type": "constructor", "id": [...], "kwargs": {...}}`, where
+/// kwargs only carries fields that differ from their defaults.
+pub fn dumpd(message: &MessageEnum) -> Value {
+    let kwargs: Map<String, Value> = message_data(message)
+        .into_iter()
+        .filter(|(key, value)| key == "content" || is_truthy(value))
+        .collect();
+
+    let mut id: Vec<&str> = LC_NAMESPACE.to_vec();
+    id.push(class_name(message));
+
+    json!({
+        "lc": 1,
+        "type": "constructor",
+        "id": id,
+        "kwargs": kwargs,
+    })
+}
+
+pub fn dumps(message: &MessageEnum) -> Result<String, ConversionError> {
+    Ok(serde_json::to_string(&dumpd(message))?)
+}
+
+/// Reads a message serialized by LangChain's `dumpd`. Any constructor id whose
+/// last segment is a known message class is accepted, including the
+/// `langchain_core` module paths and streaming chunk classes.
+pub fn load(value: &Value) -> Result<MessageEnum, ConversionError> {
+    load_at(0, value)
+}
+
+pub fn loads(input: &str) -> Result<MessageEnum, ConversionError> {
+    load(&serde_json::from_str(input)?)
+}
+
+/// Serializes a message the way LangChain's `message_to_dict` does:
+/// `{"type": "human", "data": {...}}` with every field present.
+pub fn message_to_dict(message: &MessageEnum) -> Value {
+    json!({
+        "type": message_type_name(message),
+        "data": message_data(message),
+    })
+}
+
+pub fn messages_to_dict(messages: &[MessageEnum]) -> Vec<Value> {
+    messages.iter().map(message_to_dict).collect()
+}
+
+pub fn message_from_dict(value: &Value) -> Result<MessageEnum, ConversionError> {
+    message_from_dict_at(0, value)
+}
+
+pub fn messages_from_dict(values: &[Value]) -> Result<Vec<MessageEnum>, ConversionError> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| message_from_dict_at(index, value))
+        .collect()
+}
+
+fn load_at(index: usize, value: &Value) -> Result<MessageEnum, ConversionError> {
+    if value.get("lc").and_then(Value::as_u64) != Some(1)
+        || value.get("type").and_then(Value::as_str) != Some("constructor")
+    {
+        return Err(ConversionError::InvalidPayload(format!(
+            "message {} is not a LangChain constructor",
+            index
+        )));
+    }
+
+    let class = value
+        .get("id")
+        .and_then(Value::as_array)
+        .and_then(|id| id.last())
+        .and_then(Value::as_str)
+        .ok_or(ConversionError::MissingField { index, field: "id" })?;
+    let kwargs = value.get("kwargs").ok_or(ConversionError::MissingField {
+        index,
+        field: "kwargs",
+    })?;
+
+    from_data(index, class, kwargs)
+}
+
+fn message_from_dict_at(index: usize, value: &Value) -> Result<MessageEnum, ConversionError> {
+    let kind = value
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or(ConversionError::MissingField {
+            index,
+            field: "type",
+        })?;
+    let data = value.get("data").ok_or(ConversionError::MissingField {
+        index,
+        field: "data",
+    })?;
+
+    from_data(index, kind, data)
+}
+
+fn from_data(index: usize, kind: &str, data: &Value) -> Result<MessageEnum, ConversionError> {
+    let message_type = match kind {
+        "human" | "HumanMessage" | "HumanMessageChunk" => MessageType::Human,
+        "ai" | "AIMessage" | "AIMessageChunk" => MessageType::Ai,
+        "system" | "SystemMessage" | "SystemMessageChunk" => MessageType::System,
+        "tool" | "ToolMessage" | "ToolMessageChunk" => MessageType::Tool,
+        "chat" | "ChatMessage" | "ChatMessageChunk" => MessageType::Chat,
+        "remove" | "RemoveMessage" => MessageType::Remove,
+        other => {
+            return Err(ConversionError::UnsupportedRole {
+                index,
+                role: other.to_string(),
+            })
+        }
+    };
+
+    let data = LcMessageData::deserialize(data)?;
+    let mut additional_kwargs = data.additional_kwargs.unwrap_or_default();
+    let content = from_lc_content(index, &data.content)?;
+    if serde_json::to_value(&content)? != data.content {
+        additional_kwargs.insert(CONTENT_KEY.to_string(), data.content);
+    }
+    let base = BaseMessageFields {
+        content,
+        example: data.example,
+        message_type,
+        additional_kwargs,
+        response_metadata: data.response_metadata.unwrap_or_default(),
+        id: data.id,
+        name: data.name,
+    };
+
+    let message = match message_type {
+        MessageType::Ai => MessageEnum::Ai(AiMessage {
+            tool_calls: data.tool_calls,
+            invalid_tool_calls: data.invalid_tool_calls,
+            usage_metadata: data.usage_metadata,
+            base,
+        }),
+        MessageType::Human => MessageEnum::Human(HumanMessage { base }),
+        MessageType::System => MessageEnum::System(SystemMessage { base }),
+        MessageType::Remove => {
+            if base.id.is_none() {
+                return Err(ConversionError::MissingField { index, field: "id" });
+            }
+            MessageEnum::Remove(RemoveMessage { base })
+        }
+        MessageType::Chat => {
+            let role = data.role.ok_or(ConversionError::MissingField {
+                index,
+                field: "role",
+            })?;
+            MessageEnum::Chat(ChatMessage::new_with_base(role, base))
+        }
+        MessageType::Tool => {
+            let tool_call_id = data.tool_call_id.ok_or(ConversionError::MissingField {
+                index,
+                field: "tool_call_id",
+            })?;
+            let status = match data.status.as_deref() {
+                None | Some("success") => ToolStatus::Success,
+                Some("error") => ToolStatus::Error,
+                Some(other) => {
+                    return Err(ConversionError::InvalidPayload(format!(
+                        "unknown tool status in message {}: {}",
+                        index, other
+                    )))
+                }
+            };
+            let mut base = base;
+            let artifact = match data.artifact {
+                None | Some(Value::Null) => None,
+                Some(Value::String(artifact)) => Some(artifact),
+                Some(other) => {
+                    base.additional_kwargs
+                        .insert(ARTIFACT_KEY.to_string(), other);
+                    None
+                }
+            };
+            MessageEnum::Tool(ToolMessage::new_with_base(
+                tool_call_id,
+                artifact,
+                status,
+                base,
+            ))
+        }
+    };
+
+    Ok(message)
+}
+
+/// Maps LangChain content onto `MessageContent`. Bare strings and
+/// OpenAI-style `image_url` parts are converted; parts with no equivalent,
+/// such as `tool_use` or `thinking`, are left out.
+fn from_lc_content(index: usize, content: &Value) -> Result<MessageContent, ConversionError> {
+    let parts = match content {
+        Value::String(text) => return Ok(MessageContent::Text(text.clone())),
+        Value::Array(parts) => parts,
+        other => {
+            return Err(ConversionError::InvalidPayload(format!(
+                "content of message {} must be a string or a list, found {}",
+                index, other
+            )))
+        }
+    };
+
+    let mut blocks = Vec::new();
+    for part in parts {
+        let block = match part.get("type").and_then(Value::as_str) {
+            _ if part.is_string() => part.as_str().map(ContentBlock::text),
+            Some("text") => part
+                .get("text")
+                .and_then(Value::as_str)
+                .map(ContentBlock::text),
+            Some("image_url") => from_lc_image_url(part),
+            Some("image" | "audio" | "file") => ContentBlock::deserialize(part).ok(),
+            _ => None,
+        };
+        blocks.extend(block);
+    }
+    Ok(MessageContent::Blocks(blocks))
+}
+
+/// Reads `{"type": "image_url", "image_url": {"url": ..., "detail": ...}}`,
+/// where `image_url` may also be a bare URL. `data:` URLs become base64
+/// sources.
+fn from_lc_image_url(part: &Value) -> Option<ContentBlock> {
+    let image_url = part.get("image_url")?;
+    let url = image_url
+        .as_str()
+        .or_else(|| image_url.get("url")?.as_str())?;
+    let detail = image_url
+        .get("detail")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let source = match url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+    {
+        Some((mime_type, data)) => MediaSource::Base64 {
+            mime_type: mime_type.to_string(),
+            data: data.to_string(),
+        },
+        None => MediaSource::Url {
+            url: url.to_string(),
+        },
+    };
+    Some(ContentBlock::Image { source, detail })
+}
+
+fn message_data(message: &MessageEnum) -> Map<String, Value> {
+    let mut additional_kwargs = message.additional_kwargs().clone();
+    let original_content = additional_kwargs.remove(CONTENT_KEY);
+    let artifact = additional_kwargs.remove(ARTIFACT_KEY);
+
+    let content = match original_content {
+        Some(original)
+            if from_lc_content(0, &original).as_ref() == Ok(message.message_content()) =>
+        {
+            original
+        }
+        _ => serde_json::to_value(message.message_content()).unwrap_or_default(),
+    };
+
+    let mut data = Map::new();
+    data.insert("content".to_string(), content);
+    data.insert("additional_kwargs".to_string(), json!(additional_kwargs));
+    data.insert(
+        "response_metadata".to_string(),
+        json!(message.response_metadata()),
+    );
+    data.insert("type".to_string(), json!(message_type_name(message)));
+    data.insert("name".to_string(), json!(message.name()));
+    data.insert("id".to_string(), json!(message.id()));
+
+    match message {
+        MessageEnum::Ai(message) => {
+            data.insert("example".to_string(), json!(message.is_example()));
+            data.insert("tool_calls".to_string(), json!(message.tool_calls()));
+            data.insert(
+                "invalid_tool_calls".to_string(),
+                json!(message.invalid_tool_calls()),
+            );
+            data.insert(
+                "usage_metadata".to_string(),
+                json!(message.usage_metadata()),
+            );
+        }
+        MessageEnum::Human(message) => {
+            data.insert("example".to_string(), json!(message.is_example()));
+        }
+        MessageEnum::System(_) | MessageEnum::Remove(_) => {}
+        MessageEnum::Tool(message) => {
+            data.insert("tool_call_id".to_string(), json!(message.tool_call_id()));
+            let artifact = match message.artifact() {
+                Some(artifact) => json!(artifact),
+                None => artifact.unwrap_or_default(),
+            };
+            data.insert("artifact".to_string(), artifact);
+            let status = match message.status() {
+                ToolStatus::Success => "success",
+                ToolStatus::Error => "error",
+            };
+            data.insert("status".to_string(), json!(status));
+        }
+        MessageEnum::Chat(message) => {
+            data.insert("role".to_string(), json!(message.role()));
+        }
+    }
+
+    data
+}
+
+fn message_type_name(message: &MessageEnum) -> &'static str {
+    match message {
+        MessageEnum::Ai(_) => "ai",
+        MessageEnum::Human(_) => "human",
+        MessageEnum::System(_) => "system",
+        MessageEnum::Tool(_) => "tool",
+        MessageEnum::Remove(_) => "remove",
+        MessageEnum::Chat(_) => "chat",
+    }
+}
+
+fn class_name(message: &MessageEnum) -> &'static str {
+    match message {
+        MessageEnum::Ai(_) => "AIMessage",
+        MessageEnum::Human(_) => "HumanMessage",
+        MessageEnum::System(_) => "SystemMessage",
+        MessageEnum::Tool(_) => "ToolMessage",
+        MessageEnum::Remove(_) => "RemoveMessage",
+        MessageEnum::Chat(_) => "ChatMessage",
+    }
+}
+
+/// Mirrors Python truthiness, which LangChain uses to decide which fields
+/// are worth serializing.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64() != Some(0.0),
+        Value::String(value) => !value.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dumpd_human_message() {
+        let message = MessageEnum::Human(HumanMessage::new("Hello"));
+
+        assert_eq!(
+            dumpd(&message),
+            json!({
+                "lc": 1,
+                "type": "constructor",
+                "id": ["langchain", "schema", "messages", "HumanMessage"],
+                "kwargs": {"content": "Hello", "type": "human"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_dumpd_keeps_empty_content() {
+        let ai = AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "search",
+                json!({}),
+                Some("call_1".to_string()),
+            )],
+        );
+
+        let kwargs = &dumpd(&MessageEnum::Ai(ai))["kwargs"];
+        assert_eq!(kwargs["content"], "");
+        assert_eq!(kwargs["tool_calls"][0]["type"], "tool_call");
+        assert!(kwargs.get("usage_metadata").is_none());
+    }
+
+    #[test]
+    fn test_load_langchain_core_path() {
+        let message = load(&json!({
+            "lc": 1,
+            "type": "constructor",
+            "id": ["langchain_core", "messages", "system", "SystemMessage"],
+            "kwargs": {"content": "Be brief.", "type": "system"}
+        }))
+        .unwrap();
+
+        assert_eq!(message.as_system().unwrap().content(), "Be brief.");
+    }
+
+    #[test]
+    fn test_load_rejects_non_constructor() {
+        let err = load(&json!({"lc": 1, "type": "secret", "id": ["OPENAI_API_KEY"]})).unwrap_err();
+        assert!(matches!(err, ConversionError::InvalidPayload(_)));
+    }
+
+    #[test]
+    fn test_message_to_dict_tool_message() {
+        let tool = ToolMessage::new("Failed", "call_1".to_string(), None, ToolStatus::Error);
+
+        assert_eq!(
+            message_to_dict(&MessageEnum::Tool(tool)),
+            json!({
+                "type": "tool",
+                "data": {
+                    "content": "Failed",
+                    "additional_kwargs": {},
+                    "response_metadata": {},
+                    "type": "tool",
+                    "name": null,
+                    "id": null,
+                    "tool_call_id": "call_1",
+                    "artifact": null,
+                    "status": "error"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_chat_message_round_trip() {
+        let chat = MessageEnum::Chat(ChatMessage::new("Approved", "moderator".to_string()));
+
+        let dumped = dumpd(&chat);
+        assert_eq!(
+            dumped["id"],
+            json!(["langchain", "schema", "messages", "ChatMessage"])
+        );
+        assert_eq!(dumped["kwargs"]["role"], "moderator");
+        assert_eq!(load(&dumped).unwrap(), chat);
+
+        let dict = message_to_dict(&chat);
+        assert_eq!(dict["type"], "chat");
+        assert_eq!(message_from_dict(&dict).unwrap(), chat);
+    }
+
+    #[test]
+    fn test_messages_from_dict_reports_index() {
+        let err = messages_from_dict(&[
+            json!({"type": "human", "data": {"content": "Hi"}}),
+            json!({"type": "function", "data": {"content": "{}"}}),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ConversionError::UnsupportedRole {
+                index: 1,
+                role: "function".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_lc_content_parts() {
+        let content = from_lc_content(
+            0,
+            &json!(["Hello", {"type": "thinking", "thinking": "..."}, {"type": "image_url", "image_url": "https://example.com/a.png"}]),
+        )
+        .unwrap();
+        assert_eq!(
+            content,
+            MessageContent::Blocks(vec![
+                ContentBlock::text("Hello"),
+                ContentBlock::image_url("https://example.com/a.png"),
+            ])
+        );
+
+        let err = from_lc_content(3, &json!(42)).unwrap_err();
+        assert!(matches!(err, ConversionError::InvalidPayload(_)));
+    }
+}
@@ -0,0 +1,477 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::{BaseMessage, MessageContent, MessageEnum};
+
+use super::ConversionError;
+
+const FRONT_MATTER: &str = "---";
+
+/// Renders messages as Markdown, one `## Role` section per message.
+///
+/// Text content is written as-is, or inside a ```` ```content ```` fence when
+/// it would otherwise be mistaken for layout. AI tool calls are written as
+/// ```` ```tool_call <id> ```` fences holding the call name and arguments, and
+/// tool results as ```` ```tool_result <tool_call_id> ```` fences. Anything
+/// the body cannot show, such as ids, names and metadata, goes in a front
+/// matter block that maps each message index to a JSON object of its extra
+/// fields, so [`from_markdown`] restores the messages exactly.
+pub fn to_markdown(messages: &[MessageEnum]) -> Result<String, ConversionError> {
+    let mut front_matter = Vec::new();
+    let mut body = String::new();
+
+    for (index, message) in messages.iter().enumerate() {
+        let mut fields: Map<String, Value> =
+            serde_json::from_value(serde_json::to_value(message)?)?;
+        fields.remove("message_type");
+        if fields.get("example") == Some(&Value::Bool(false)) {
+            fields.remove("example");
+        }
+
+        body.push_str(&heading(message, &mut fields));
+        body.push_str("\n\n");
+
+        let text = match message.message_content() {
+            MessageContent::Text(text) if !text.contains('\r') => {
+                fields.remove("content");
+                text.as_str()
+            }
+            _ => "",
+        };
+
+        match message {
+            MessageEnum::Tool(tool) => {
+                let id = token(tool.tool_call_id());
+                if id.is_some() {
+                    fields.remove("tool_call_id");
+                }
+                if fields.get("status") == Some(&json!("Success")) {
+                    fields.remove("status");
+                }
+                push_fence(&mut body, &fence_info("tool_result", id), text);
+            }
+            _ => {
+                if !text.is_empty() {
+                    if needs_fence(text) {
+                        push_fence(&mut body, "content", text);
+                    } else {
+                        body.push_str(text);
+                        body.push_str("\n\n");
+                    }
+                }
+            }
+        }
+
+        if let MessageEnum::Ai(ai) = message {
+            let ids: Option<Vec<&str>> = ai
+                .tool_calls()
+                .iter()
+                .map(|call| token(call.id()?))
+                .collect();
+            if let Some(ids) = ids {
+                fields.remove("tool_calls");
+                for (call, id) in ai.tool_calls().iter().zip(ids) {
+                    let call = serde_json::to_string_pretty(&FencedToolCall {
+                        name: call.name(),
+                        args: call.args(),
+                    })?;
+                    push_fence(&mut body, &fence_info("tool_call", Some(id)), &call);
+                }
+            }
+        }
+
+        if !fields.is_empty() {
+            front_matter.push(format!("{}: {}", index, Value::Object(fields)));
+        }
+    }
+
+    let mut markdown = String::new();
+    if !front_matter.is_empty() {
+        markdown.push_str(FRONT_MATTER);
+        markdown.push('\n');
+        for line in front_matter {
+            markdown.push_str(&line);
+            markdown.push('\n');
+        }
+        markdown.push_str(FRONT_MATTER);
+        markdown.push_str("\n\n");
+    }
+    markdown.push_str(body.trim_end_matches('\n'));
+    markdown.push('\n');
+    Ok(markdown)
+}
+
+/// Reads messages back from the layout written by [`to_markdown`].
+pub fn from_markdown(input: &str) -> Result<Vec<MessageEnum>, ConversionError> {
+    let mut lines = input.lines().enumerate().peekable();
+    let mut extra_fields = BTreeMap::new();
+
+    if lines.peek().is_some_and(|(_, line)| *line == FRONT_MATTER) {
+        lines.next();
+        loop {
+            let (number, line) = lines
+                .next()
+                .ok_or_else(|| invalid(1, "unterminated front matter"))?;
+            if line == FRONT_MATTER {
+                break;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (index, fields) = line
+                .split_once(": ")
+                .ok_or_else(|| invalid(number + 1, "expected `<index>: <json>`"))?;
+            let index: usize = index
+                .trim()
+                .parse()
+                .map_err(|_| invalid(number + 1, "expected a message index"))?;
+            let fields: Map<String, Value> = serde_json::from_str(fields)
+                .map_err(|err| invalid(number + 1, &format!("invalid JSON: {}", err)))?;
+            extra_fields.insert(index, fields);
+        }
+    }
+
+    let mut sections: Vec<Section> = Vec::new();
+    while let Some((number, line)) = lines.next() {
+        if let Some(role) = parse_heading(line) {
+            sections.push(Section::new(role));
+            continue;
+        }
+
+        let Some(section) = sections.last_mut() else {
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Err(invalid(number + 1, "expected a `## Role` heading"));
+        };
+
+        let Some((fence, info)) = parse_fence_open(line) else {
+            section.text.push(line);
+            continue;
+        };
+        let mut fenced_lines = Vec::new();
+        let closing = loop {
+            let (_, next) = lines
+                .next()
+                .ok_or_else(|| invalid(number + 1, "unterminated code fence"))?;
+            if next == fence {
+                break next;
+            }
+            fenced_lines.push(next);
+        };
+        let fenced = fenced_lines.join("\n");
+
+        match info.split_once(' ').unwrap_or((info, "")) {
+            ("content", _) => section.content = Some(fenced),
+            ("tool_result", id) => {
+                section.tool_call_id = Some(id.to_string());
+                section.content = Some(fenced);
+            }
+            ("tool_call", id) => {
+                let call: Map<String, Value> = serde_json::from_str(&fenced)
+                    .map_err(|err| invalid(number + 1, &format!("invalid tool call: {}", err)))?;
+                section.tool_calls.push((id.to_string(), call));
+            }
+            _ => {
+                section.text.push(line);
+                section.text.extend(fenced_lines);
+                section.text.push(closing);
+            }
+        }
+    }
+
+    if let Some(index) = extra_fields.keys().find(|index| **index >= sections.len()) {
+        return Err(ConversionError::InvalidPayload(format!(
+            "front matter refers to message {} but there are {} messages",
+            index,
+            sections.len()
+        )));
+    }
+
+    sections
+        .into_iter()
+        .enumerate()
+        .map(|(index, section)| {
+            let mut fields = section.into_fields();
+            if let Some(extra) = extra_fields.remove(&index) {
+                fields.extend(extra);
+            }
+            serde_json::from_value(Value::Object(fields)).map_err(|err| {
+                ConversionError::InvalidPayload(format!("message {}: {}", index, err))
+            })
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct FencedToolCall<'a> {
+    name: &'a str,
+    args: &'a Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Heading {
+    Standard(&'static str),
+    Chat(Option<String>),
+}
+
+struct Section<'a> {
+    heading: Heading,
+    text: Vec<&'a str>,
+    content: Option<String>,
+    tool_call_id: Option<String>,
+    tool_calls: Vec<(String, Map<String, Value>)>,
+}
+
+impl<'a> Section<'a> {
+    fn new(heading: Heading) -> Self {
+        Section {
+            heading,
+            text: Vec::new(),
+            content: None,
+            tool_call_id: None,
+            tool_calls: Vec::new(),
+        }
+    }
+
+    fn into_fields(self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        let is_tool = self.heading == Heading::Standard("tool");
+        match self.heading {
+            Heading::Standard(role) => {
+                fields.insert("role".to_string(), json!(role));
+            }
+            Heading::Chat(role) => {
+                if let Some(role) = role {
+                    fields.insert("role".to_string(), json!(role));
+                }
+                fields.insert("message_type".to_string(), json!("Chat"));
+            }
+        }
+
+        let content = self.content.unwrap_or_else(|| {
+            let text = &self.text;
+            let start = text.iter().position(|line| !line.trim().is_empty());
+            let end = text.iter().rposition(|line| !line.trim().is_empty());
+            match (start, end) {
+                (Some(start), Some(end)) => text[start..=end].join("\n"),
+                _ => String::new(),
+            }
+        });
+        fields.insert("content".to_string(), json!(content));
+
+        if let Some(tool_call_id) = self.tool_call_id.filter(|id| !id.is_empty()) {
+            fields.insert("tool_call_id".to_string(), json!(tool_call_id));
+        }
+        if is_tool {
+            fields.insert("status".to_string(), json!("Success"));
+        }
+
+        if !self.tool_calls.is_empty() {
+            let calls: Vec<Value> = self
+                .tool_calls
+                .into_iter()
+                .map(|(id, mut call)| {
+                    call.insert("id".to_string(), json!(id));
+                    call.insert("type".to_string(), json!("tool_call"));
+                    Value::Object(call)
+                })
+                .collect();
+            fields.insert("tool_calls".to_string(), Value::Array(calls));
+        }
+        fields
+    }
+}
+
+fn heading(message: &MessageEnum, fields: &mut Map<String, Value>) -> String {
+    let title = match message {
+        MessageEnum::Human(_) => "Human",
+        MessageEnum::Ai(_) => "AI",
+        MessageEnum::System(_) => "System",
+        MessageEnum::Tool(_) => "Tool",
+        MessageEnum::Remove(_) => "Remove",
+        MessageEnum::Chat(chat) => {
+            let role = chat.role();
+            let printable = !role.is_empty() && role.trim() == role && !role.contains('\n');
+            if printable {
+                fields.remove("role");
+                return format!("## Chat: {}", role);
+            }
+            return "## Chat".to_string();
+        }
+    };
+    fields.remove("role");
+    format!("## {}", title)
+}
+
+fn parse_heading(line: &str) -> Option<Heading> {
+    let title = line.strip_prefix("## ")?;
+    if let Some(role) = title.strip_prefix("Chat: ") {
+        return Some(Heading::Chat(Some(role.to_string())));
+    }
+    let heading = match title.to_lowercase().as_str() {
+        "human" => Heading::Standard("human"),
+        "ai" => Heading::Standard("ai"),
+        "system" => Heading::Standard("system"),
+        "tool" => Heading::Standard("tool"),
+        "remove" => Heading::Standard("remove"),
+        "chat" => Heading::Chat(None),
+        _ => return None,
+    };
+    Some(heading)
+}
+
+fn parse_fence_open(line: &str) -> Option<(&str, &str)> {
+    let ticks = line.len() - line.trim_start_matches('`').len();
+    (ticks >= 3).then(|| (&line[..ticks], line[ticks..].trim()))
+}
+
+/// Text written outside a fence must survive the importer trimming blank
+/// lines and must not look like a heading or a fence.
+fn needs_fence(text: &str) -> bool {
+    let first = text.lines().next().unwrap_or_default();
+    let last = text.lines().last().unwrap_or_default();
+    first.trim().is_empty()
+        || last.trim().is_empty()
+        || text.ends_with('\n')
+        || text
+            .lines()
+            .any(|line| parse_heading(line).is_some() || parse_fence_open(line).is_some())
+}
+
+fn push_fence(body: &mut String, info: &str, text: &str) {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run.max(2) + 1);
+
+    body.push_str(&fence);
+    body.push_str(info);
+    body.push('\n');
+    if !text.is_empty() {
+        body.push_str(text);
+        body.push('\n');
+    }
+    body.push_str(&fence);
+    body.push_str("\n\n");
+}
+
+fn fence_info(kind: &str, id: Option<&str>) -> String {
+    match id {
+        Some(id) => format!("{} {}", kind, id),
+        None => kind.to_string(),
+    }
+}
+
+/// Ids are written in fence info strings, so they must be a single word.
+fn token(id: &str) -> Option<&str> {
+    (!id.is_empty() && !id.contains(char::is_whitespace)).then_some(id)
+}
+
+fn invalid(line: usize, reason: &str) -> ConversionError {
+    ConversionError::InvalidPayload(format!("line {}: {}", line, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_message::ToolStatus;
+    use crate::{
+        AiMessage, ChatMessage, ContentBlock, HumanMessage, InvalidToolCall, RemoveMessage,
+        ToolCall, ToolMessage, UsageMetadata,
+    };
+
+    fn round_trip(messages: Vec<MessageEnum>) {
+        let markdown = to_markdown(&messages).unwrap();
+        assert_eq!(from_markdown(&markdown).unwrap(), messages, "{}", markdown);
+    }
+
+    #[test]
+    fn test_round_trip_content_that_looks_like_layout() {
+        round_trip(vec![
+            MessageEnum::Human(HumanMessage::new("## Human\nnot a heading")),
+            MessageEnum::Ai(AiMessage::new("```rust\nfn main() {}\n```")),
+            MessageEnum::Human(HumanMessage::new("\n\nleading blank lines")),
+            MessageEnum::Human(HumanMessage::new("trailing newline\n")),
+            MessageEnum::Human(HumanMessage::new("windows\r\nline endings")),
+            MessageEnum::Human(HumanMessage::new("")),
+            MessageEnum::Human(HumanMessage::new("---\n# Title\n\n---")),
+        ]);
+    }
+
+    #[test]
+    fn test_round_trip_every_field() {
+        let mut ai = AiMessage::new(vec![ContentBlock::text("See image")]);
+        ai.set_tool_calls(vec![ToolCall::new("lookup", json!({}), None)]);
+        ai.set_invalid_tool_calls(vec![InvalidToolCall {
+            name: Some("broken".to_string()),
+            args: Some("{".to_string()),
+            id: Some("call_9".to_string()),
+            error: None,
+        }]);
+        ai.set_usage_metadata(Some(UsageMetadata::new(12, 3)));
+        ai.set_example(true);
+        ai.insert_additional_kwarg("refusal", "none").unwrap();
+        ai.insert_response_metadata("model_name", "gpt-4o").unwrap();
+
+        let mut tool = ToolMessage::new(
+            "```\nnested fence\n```",
+            "call with spaces".to_string(),
+            Some("artifact-1".to_string()),
+            ToolStatus::Error,
+        );
+        tool.set_name(Some("search".to_string()));
+
+        round_trip(vec![
+            MessageEnum::Human(HumanMessage::new(vec![
+                ContentBlock::text("What is this?"),
+                ContentBlock::image_url("https://example.com/cat.png"),
+            ])),
+            MessageEnum::Ai(ai),
+            MessageEnum::Tool(tool),
+            MessageEnum::Chat(ChatMessage::new("Odd role", " padded ".to_string())),
+            MessageEnum::Chat(ChatMessage::new("Standard name", "human".to_string())),
+            MessageEnum::Remove(RemoveMessage::with_id("msg_1")),
+        ]);
+    }
+
+    #[test]
+    fn test_import_hand_written_markdown() {
+        let markdown =
+            "\n## human\n\nHere is code:\n\n```python\nprint('hi')\n```\n\n## ai\nDone.\n";
+
+        let messages = from_markdown(markdown).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].content(),
+            "Here is code:\n\n```python\nprint('hi')\n```"
+        );
+        assert_eq!(messages[1].content(), "Done.");
+        assert!(from_markdown("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let err = from_markdown("Hello\n## Human\nHi").unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError::InvalidPayload("line 1: expected a `## Role` heading".to_string())
+        );
+
+        let err = from_markdown("## AI\n\n```tool_call call_1\n{").unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError::InvalidPayload("line 3: unterminated code fence".to_string())
+        );
+
+        let err = from_markdown("---\n3: {}\n---\n## Human\nHi").unwrap_err();
+        assert!(err.to_string().contains("front matter refers to message 3"));
+
+        let err = from_markdown("## Remove\n").unwrap_err();
+        assert!(err.to_string().contains("Missing id for RemoveMessage"));
+    }
+}
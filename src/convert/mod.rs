@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::{
+    AiMessage, BaseMessage, BaseMessageFields, ChatMessage, HumanMessage, MessageEnum, MessageType,
+    SystemMessage,
+};
+
+pub mod anthropic;
+pub mod gemini;
+pub mod langchain;
+pub mod markdown;
+pub mod openai;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    UnsupportedContent { index: usize, reason: String },
+    UnsupportedRole { index: usize, role: String },
+    MissingField { index: usize, field: &'static str },
+    InvalidSequence { index: usize, reason: String },
+    InvalidPayload(String),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::UnsupportedContent { index, reason } => {
+                write!(f, "Unsupported content in message {}: {}", index, reason)
+            }
+            ConversionError::UnsupportedRole { index, role } => {
+                write!(f, "Unsupported role in message {}: {}", index, role)
+            }
+            ConversionError::MissingField { index, field } => {
+                write!(f, "Missing field in message {}: {}", index, field)
+            }
+            ConversionError::InvalidSequence { index, reason } => {
+                write!(
+                    f,
+                    "Invalid message sequence at message {}: {}",
+                    index, reason
+                )
+            }
+            ConversionError::InvalidPayload(reason) => write!(f, "Invalid payload: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl From<serde_json::Error> for ConversionError {
+    fn from(err: serde_json::Error) -> Self {
+        ConversionError::InvalidPayload(err.to_string())
+    }
+}
+
+/// Provider formats only know a fixed set of roles, so a `ChatMessage` is
+/// sent as the standard message its role names, if there is one.
+pub(crate) fn resolve_chat_role(
+    index: usize,
+    message: &MessageEnum,
+) -> Result<Cow<'_, MessageEnum>, ConversionError> {
+    let chat = match message {
+        MessageEnum::Chat(chat) => chat,
+        message => return Ok(Cow::Borrowed(message)),
+    };
+
+    let resolved = match chat.role().to_lowercase().as_str() {
+        "user" | "human" => MessageEnum::Human(HumanMessage {
+            base: chat_base(chat, MessageType::Human),
+        }),
+        "assistant" | "ai" | "model" => MessageEnum::Ai(AiMessage {
+            tool_calls: Vec::new(),
+            invalid_tool_calls: Vec::new(),
+            usage_metadata: None,
+            base: chat_base(chat, MessageType::Ai),
+        }),
+        "system" | "developer" => MessageEnum::System(SystemMessage {
+            base: chat_base(chat, MessageType::System),
+        }),
+        _ => {
+            return Err(ConversionError::UnsupportedRole {
+                index,
+                role: chat.role().to_string(),
+            })
+        }
+    };
+    Ok(Cow::Owned(resolved))
+}
+
+fn chat_base(chat: &ChatMessage, message_type: MessageType) -> BaseMessageFields {
+    BaseMessageFields {
+        content: chat.message_content().clone(),
+        example: chat.is_example(),
+        message_type,
+        additional_kwargs: chat.additional_kwargs().clone(),
+        response_metadata: chat.response_metadata().clone(),
+        id: chat.id().map(str::to_string),
+        name: chat.name().map(str::to_string),
+    }
+}
+
+pub(crate) fn data_url(mime_type: &str, data: &str) -> String {
+    format!("data:{};base64,{}", mime_type, data)
+}
+
+pub(crate) fn parse_data_url(url: &str) -> Option<(&str, &str)> {
+    url.strip_prefix("data:")?.split_once(";base64,")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_url_round_trip() {
+        let url = data_url("image/png", "iVBORw0KGgo=");
+        assert_eq!(url, "data:image/png;base64,iVBORw0KGgo=");
+        assert_eq!(parse_data_url(&url), Some(("image/png", "iVBORw0KGgo=")));
+        assert_eq!(parse_data_url("https://example.com/cat.png"), None);
+    }
+
+    #[test]
+    fn test_resolve_chat_role() {
+        let chat = MessageEnum::Chat(ChatMessage::new("Hi", "User".to_string()));
+        let resolved = resolve_chat_role(0, &chat).unwrap();
+        assert_eq!(
+            resolved.as_human().map(|message| message.content()),
+            Some("Hi".into())
+        );
+
+        let custom = MessageEnum::Chat(ChatMessage::new("Hi", "moderator".to_string()));
+        assert_eq!(
+            resolve_chat_role(3, &custom).unwrap_err(),
+            ConversionError::UnsupportedRole {
+                index: 3,
+                role: "moderator".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_conversion_error_display() {
+        let err = ConversionError::MissingField {
+            index: 2,
+            field: "tool_call_id",
+        };
+        assert_eq!(err.to_string(), "Missing field in message 2: tool_call_id");
+    }
+}
@@ -0,0 +1,731 @@
+use serde::{Deserialize, Serialize};
+
+use super::{data_url, parse_data_url, resolve_chat_role, ConversionError};
+use crate::tool_message::ToolStatus;
+use crate::usage_metadata::{InputTokenDetails, OutputTokenDetails, UsageMetadata};
+use crate::{
+    AiMessage, BaseMessage, ContentBlock, HumanMessage, InvalidToolCall, MediaSource,
+    MessageContent, MessageEnum, SystemMessage, ToolCall, ToolMessage,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "role", rename_all = "lowercase")]
+pub enum OpenAiMessage {
+    #[serde(alias = "developer")]
+    System {
+        content: OpenAiContent,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        name: Option<String>,
+    },
+    User {
+        content: OpenAiContent,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        name: Option<String>,
+    },
+    Assistant {
+        #[serde(default)]
+        content: Option<OpenAiContent>,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        name: Option<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        tool_calls: Vec<OpenAiToolCall>,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        refusal: Option<String>,
+    },
+    Tool {
+        content: OpenAiContent,
+        tool_call_id: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum OpenAiContent {
+    Text(String),
+    Parts(Vec<OpenAiContentPart>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpenAiContentPart {
+    Text { text: String },
+    ImageUrl { image_url: OpenAiImageUrl },
+    InputAudio { input_audio: OpenAiInputAudio },
+    File { file: OpenAiFile },
+    Refusal { refusal: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiImageUrl {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiInputAudio {
+    pub data: String,
+    pub format: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiFile {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub file_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub file_data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub filename: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename = "function")]
+pub struct OpenAiToolCall {
+    pub id: String,
+    pub function: OpenAiFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiFunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiChatCompletion {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub system_fingerprint: Option<String>,
+    pub choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    pub usage: Option<OpenAiUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiChoice {
+    #[serde(default)]
+    pub index: usize,
+    pub message: OpenAiMessage,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    #[serde(default)]
+    pub prompt_tokens_details: Option<OpenAiPromptTokensDetails>,
+    #[serde(default)]
+    pub completion_tokens_details: Option<OpenAiCompletionTokensDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiPromptTokensDetails {
+    #[serde(default)]
+    pub cached_tokens: Option<u64>,
+    #[serde(default)]
+    pub audio_tokens: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenAiCompletionTokensDetails {
+    #[serde(default)]
+    pub reasoning_tokens: Option<u64>,
+    #[serde(default)]
+    pub audio_tokens: Option<u64>,
+}
+
+impl From<&OpenAiUsage> for UsageMetadata {
+    fn from(usage: &OpenAiUsage) -> Self {
+        UsageMetadata {
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            input_token_details: usage.prompt_tokens_details.as_ref().map(|details| {
+                InputTokenDetails {
+                    audio: details.audio_tokens,
+                    cache_creation: None,
+                    cache_read: details.cached_tokens,
+                }
+            }),
+            output_token_details: usage.completion_tokens_details.as_ref().map(|details| {
+                OutputTokenDetails {
+                    audio: details.audio_tokens,
+                    reasoning: details.reasoning_tokens,
+                }
+            }),
+        }
+    }
+}
+
+/// Converts a conversation into OpenAI Chat Completions request messages.
+pub fn to_openai_messages(messages: &[MessageEnum]) -> Result<Vec<OpenAiMessage>, ConversionError> {
+    messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| to_openai_message(index, message))
+        .collect()
+}
+
+/// Converts OpenAI messages, such as a stored request history, back into
+/// `MessageEnum` values.
+pub fn from_openai_messages(
+    messages: &[OpenAiMessage],
+) -> Result<Vec<MessageEnum>, ConversionError> {
+    messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| from_openai_message_at(index, message))
+        .collect()
+}
+
+pub fn from_openai_message(message: &OpenAiMessage) -> Result<MessageEnum, ConversionError> {
+    from_openai_message_at(0, message)
+}
+
+/// Converts the first choice of a Chat Completions response into an
+/// `AiMessage`, carrying over the response id, model, finish reason and usage.
+pub fn from_openai_completion(
+    completion: &OpenAiChatCompletion,
+) -> Result<AiMessage, ConversionError> {
+    let choice = completion.choices.first().ok_or_else(|| {
+        ConversionError::InvalidPayload("Chat completion has no choices".to_string())
+    })?;
+
+    let mut message = match from_openai_message_at(choice.index, &choice.message)? {
+        MessageEnum::Ai(message) => message,
+        other => {
+            return Err(ConversionError::UnsupportedRole {
+                index: choice.index,
+                role: other.role().to_string(),
+            })
+        }
+    };
+
+    message.set_id(completion.id.clone());
+    let metadata = message.response_metadata_mut();
+    if let Some(model) = &completion.model {
+        metadata.insert("model_name".to_string(), model.as_str().into());
+    }
+    if let Some(fingerprint) = &completion.system_fingerprint {
+        metadata.insert(
+            "system_fingerprint".to_string(),
+            fingerprint.as_str().into(),
+        );
+    }
+    if let Some(finish_reason) = &choice.finish_reason {
+        metadata.insert("finish_reason".to_string(), finish_reason.as_str().into());
+    }
+    message.set_usage_metadata(completion.usage.as_ref().map(UsageMetadata::from));
+
+    Ok(message)
+}
+
+fn to_openai_message(
+    index: usize,
+    message: &MessageEnum,
+) -> Result<OpenAiMessage, ConversionError> {
+    let message = resolve_chat_role(index, message)?;
+    let name = message.name().map(str::to_string);
+    match message.as_ref() {
+        MessageEnum::System(message) => Ok(OpenAiMessage::System {
+            content: to_openai_text_content(index, message.message_content())?,
+            name,
+        }),
+        MessageEnum::Human(message) => Ok(OpenAiMessage::User {
+            content: to_openai_content(index, message.message_content())?,
+            name,
+        }),
+        MessageEnum::Ai(message) => {
+            let tool_calls = message
+                .tool_calls()
+                .iter()
+                .map(|tool_call| to_openai_tool_call(index, tool_call))
+                .chain(
+                    message
+                        .invalid_tool_calls()
+                        .iter()
+                        .map(|tool_call| to_openai_invalid_tool_call(index, tool_call)),
+                )
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let content = if message.message_content().is_empty() && !tool_calls.is_empty() {
+                None
+            } else {
+                Some(to_openai_text_content(index, message.message_content())?)
+            };
+
+            Ok(OpenAiMessage::Assistant {
+                content,
+                name,
+                tool_calls,
+                refusal: message.get_additional_kwarg("refusal")?,
+            })
+        }
+        MessageEnum::Tool(message) => Ok(OpenAiMessage::Tool {
+            content: to_openai_text_content(index, message.message_content())?,
+            tool_call_id: message.tool_call_id().to_string(),
+        }),
+        message @ (MessageEnum::Chat(_) | MessageEnum::Remove(_)) => {
+            Err(ConversionError::UnsupportedRole {
+                index,
+                role: message.role().to_string(),
+            })
+        }
+    }
+}
+
+fn to_openai_tool_call(
+    index: usize,
+    tool_call: &ToolCall,
+) -> Result<OpenAiToolCall, ConversionError> {
+    let id = tool_call.id().ok_or(ConversionError::MissingField {
+        index,
+        field: "tool_calls.id",
+    })?;
+    Ok(OpenAiToolCall {
+        id: id.to_string(),
+        function: OpenAiFunctionCall {
+            name: tool_call.name().to_string(),
+            arguments: serde_json::to_string(tool_call.args())?,
+        },
+    })
+}
+
+fn to_openai_invalid_tool_call(
+    index: usize,
+    tool_call: &InvalidToolCall,
+) -> Result<OpenAiToolCall, ConversionError> {
+    let missing = |field| ConversionError::MissingField { index, field };
+    Ok(OpenAiToolCall {
+        id: tool_call
+            .id
+            .clone()
+            .ok_or_else(|| missing("invalid_tool_calls.id"))?,
+        function: OpenAiFunctionCall {
+            name: tool_call
+                .name
+                .clone()
+                .ok_or_else(|| missing("invalid_tool_calls.name"))?,
+            arguments: tool_call.args.clone().unwrap_or_default(),
+        },
+    })
+}
+
+fn to_openai_content(
+    index: usize,
+    content: &MessageContent,
+) -> Result<OpenAiContent, ConversionError> {
+    match content {
+        MessageContent::Text(text) => Ok(OpenAiContent::Text(text.clone())),
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .map(|block| to_openai_part(index, block))
+            .collect::<Result<Vec<_>, _>>()
+            .map(OpenAiContent::Parts),
+    }
+}
+
+/// System, assistant and tool messages only accept text parts.
+fn to_openai_text_content(
+    index: usize,
+    content: &MessageContent,
+) -> Result<OpenAiContent, ConversionError> {
+    match content
+        .blocks()
+        .iter()
+        .find(|block| block.as_text().is_none())
+    {
+        Some(block) => Err(ConversionError::UnsupportedContent {
+            index,
+            reason: format!(
+                "only text content is accepted for this role, found {:?}",
+                block
+            ),
+        }),
+        None => to_openai_content(index, content),
+    }
+}
+
+fn to_openai_part(
+    index: usize,
+    block: &ContentBlock,
+) -> Result<OpenAiContentPart, ConversionError> {
+    let part = match block {
+        ContentBlock::Text { text } => OpenAiContentPart::Text { text: text.clone() },
+        ContentBlock::Image {
+            source: MediaSource::Url { url },
+            detail,
+        } => OpenAiContentPart::ImageUrl {
+            image_url: OpenAiImageUrl {
+                url: url.clone(),
+                detail: detail.clone(),
+            },
+        },
+        ContentBlock::Image {
+            source: MediaSource::Base64 { mime_type, data },
+            detail,
+        } => OpenAiContentPart::ImageUrl {
+            image_url: OpenAiImageUrl {
+                url: data_url(mime_type, data),
+                detail: detail.clone(),
+            },
+        },
+        ContentBlock::Audio {
+            source: MediaSource::Base64 { mime_type, data },
+        } => OpenAiContentPart::InputAudio {
+            input_audio: OpenAiInputAudio {
+                data: data.clone(),
+                format: audio_format(mime_type).to_string(),
+            },
+        },
+        ContentBlock::File {
+            source: MediaSource::Id { id },
+            filename,
+        } => OpenAiContentPart::File {
+            file: OpenAiFile {
+                file_id: Some(id.clone()),
+                file_data: None,
+                filename: filename.clone(),
+            },
+        },
+        ContentBlock::File {
+            source: MediaSource::Base64 { mime_type, data },
+            filename,
+        } => OpenAiContentPart::File {
+            file: OpenAiFile {
+                file_id: None,
+                file_data: Some(data_url(mime_type, data)),
+                filename: filename.clone(),
+            },
+        },
+        unsupported => {
+            return Err(ConversionError::UnsupportedContent {
+                index,
+                reason: format!("OpenAI cannot represent {:?}", unsupported),
+            })
+        }
+    };
+    Ok(part)
+}
+
+fn audio_format(mime_type: &str) -> &str {
+    match mime_type {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        other => other.strip_prefix("audio/").unwrap_or(other),
+    }
+}
+
+fn audio_mime_type(format: &str) -> String {
+    match format {
+        "mp3" => "audio/mpeg".to_string(),
+        other => format!("audio/{}", other),
+    }
+}
+
+fn from_openai_message_at(
+    index: usize,
+    message: &OpenAiMessage,
+) -> Result<MessageEnum, ConversionError> {
+    let message = match message {
+        OpenAiMessage::System { content, name } => {
+            let mut system = SystemMessage::new(from_openai_content(index, content)?);
+            system.set_name(name.clone());
+            MessageEnum::System(system)
+        }
+        OpenAiMessage::User { content, name } => {
+            let mut human = HumanMessage::new(from_openai_content(index, content)?);
+            human.set_name(name.clone());
+            MessageEnum::Human(human)
+        }
+        OpenAiMessage::Assistant {
+            content,
+            name,
+            tool_calls,
+            refusal,
+        } => {
+            // Refusal parts are kept under `refusal`, as the top-level field
+            // is, rather than read as text the model said.
+            let (content, refusal_parts) = match content {
+                Some(OpenAiContent::Parts(parts)) => {
+                    let mut refusals = Vec::new();
+                    let mut kept = Vec::new();
+                    for part in parts {
+                        match part {
+                            OpenAiContentPart::Refusal { refusal } => {
+                                refusals.push(refusal.as_str())
+                            }
+                            part => kept.push(part.clone()),
+                        }
+                    }
+                    let content = if kept.is_empty() {
+                        MessageContent::default()
+                    } else {
+                        from_openai_content(index, &OpenAiContent::Parts(kept))?
+                    };
+                    (content, (!refusals.is_empty()).then(|| refusals.concat()))
+                }
+                Some(content) => (from_openai_content(index, content)?, None),
+                None => (MessageContent::default(), None),
+            };
+            let refusal = refusal.clone().or(refusal_parts);
+            let mut ai = AiMessage::new(content);
+            ai.set_name(name.clone());
+            for tool_call in tool_calls {
+                match ToolCall::try_from_raw(
+                    Some(tool_call.function.name.clone()),
+                    Some(tool_call.function.arguments.clone()),
+                    Some(tool_call.id.clone()),
+                ) {
+                    Ok(tool_call) => ai.tool_calls.push(tool_call),
+                    Err(invalid) => ai.invalid_tool_calls.push(invalid),
+                }
+            }
+            if let Some(refusal) = refusal {
+                ai.insert_additional_kwarg("refusal", refusal)?;
+            }
+            MessageEnum::Ai(ai)
+        }
+        OpenAiMessage::Tool {
+            content,
+            tool_call_id,
+        } => MessageEnum::Tool(ToolMessage::new(
+            from_openai_content(index, content)?,
+            tool_call_id.clone(),
+            None,
+            ToolStatus::Success,
+        )),
+    };
+    Ok(message)
+}
+
+fn from_openai_content(
+    index: usize,
+    content: &OpenAiContent,
+) -> Result<MessageContent, ConversionError> {
+    match content {
+        OpenAiContent::Text(text) => Ok(MessageContent::Text(text.clone())),
+        OpenAiContent::Parts(parts) => parts
+            .iter()
+            .map(|part| from_openai_part(index, part))
+            .collect::<Result<Vec<_>, _>>()
+            .map(MessageContent::Blocks),
+    }
+}
+
+fn from_openai_part(
+    index: usize,
+    part: &OpenAiContentPart,
+) -> Result<ContentBlock, ConversionError> {
+    let block = match part {
+        OpenAiContentPart::Text { text } => ContentBlock::text(text.as_str()),
+        OpenAiContentPart::Refusal { .. } => {
+            return Err(ConversionError::UnsupportedContent {
+                index,
+                reason: "refusal parts are only valid in assistant messages".to_string(),
+            })
+        }
+        OpenAiContentPart::ImageUrl { image_url } => ContentBlock::Image {
+            source: media_source_from_url(&image_url.url),
+            detail: image_url.detail.clone(),
+        },
+        OpenAiContentPart::InputAudio { input_audio } => ContentBlock::audio_base64(
+            audio_mime_type(&input_audio.format),
+            input_audio.data.as_str(),
+        ),
+        OpenAiContentPart::File { file } => {
+            let source = match (&file.file_id, &file.file_data) {
+                (Some(id), _) => MediaSource::Id { id: id.clone() },
+                (None, Some(file_data)) => {
+                    let (mime_type, data) = parse_data_url(file_data).ok_or_else(|| {
+                        ConversionError::UnsupportedContent {
+                            index,
+                            reason: "file_data must be a base64 data URL".to_string(),
+                        }
+                    })?;
+                    MediaSource::Base64 {
+                        mime_type: mime_type.to_string(),
+                        data: data.to_string(),
+                    }
+                }
+                (None, None) => {
+                    return Err(ConversionError::MissingField {
+                        index,
+                        field: "file.file_id",
+                    })
+                }
+            };
+            ContentBlock::File {
+                source,
+                filename: file.filename.clone(),
+            }
+        }
+    };
+    Ok(block)
+}
+
+fn media_source_from_url(url: &str) -> MediaSource {
+    match parse_data_url(url) {
+        Some((mime_type, data)) => MediaSource::Base64 {
+            mime_type: mime_type.to_string(),
+            data: data.to_string(),
+        },
+        None => MediaSource::Url {
+            url: url.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_human_message_to_openai() {
+        let mut human = HumanMessage::new("Hello!");
+        human.set_name(Some("alice".to_string()));
+
+        let converted = to_openai_messages(&[human.into()]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            json!([{"role": "user", "content": "Hello!", "name": "alice"}])
+        );
+    }
+
+    #[test]
+    fn test_ai_tool_calls_to_openai() {
+        let ai = AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "get_weather",
+                json!({"city": "Paris"}),
+                Some("call_1".to_string()),
+            )],
+        );
+
+        let converted = to_openai_messages(&[ai.into()]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            json!([{
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "type": "function",
+                    "id": "call_1",
+                    "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+                }]
+            }])
+        );
+    }
+
+    #[test]
+    fn test_tool_call_without_id_is_rejected() {
+        let ai =
+            AiMessage::new_with_tool_calls("", vec![ToolCall::new("get_weather", json!({}), None)]);
+
+        let err = to_openai_messages(&[HumanMessage::new("Hi").into(), ai.into()]).unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError::MissingField {
+                index: 1,
+                field: "tool_calls.id"
+            }
+        );
+    }
+
+    #[test]
+    fn test_system_message_rejects_images() {
+        let system = SystemMessage::new(vec![ContentBlock::image_url("https://example.com/a.png")]);
+
+        let err = to_openai_messages(&[system.into()]).unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::UnsupportedContent { index: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn test_base64_image_uses_data_url() {
+        let human = HumanMessage::new(vec![ContentBlock::image_base64(
+            "image/png",
+            "iVBORw0KGgo=",
+        )]);
+
+        let converted = to_openai_messages(&[human.clone().into()]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap()[0]["content"][0]["image_url"]["url"],
+            "data:image/png;base64,iVBORw0KGgo="
+        );
+
+        let round_trip = from_openai_messages(&converted).unwrap();
+        assert_eq!(round_trip, vec![MessageEnum::Human(human)]);
+    }
+
+    #[test]
+    fn test_assistant_with_malformed_arguments() {
+        let message: OpenAiMessage = serde_json::from_value(json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "get_weather", "arguments": "{\"city\": "}
+            }]
+        }))
+        .unwrap();
+
+        let converted = from_openai_message(&message).unwrap();
+        let ai = converted.as_ai().unwrap();
+        assert!(ai.tool_calls().is_empty());
+        assert_eq!(ai.invalid_tool_calls()[0].id.as_deref(), Some("call_1"));
+    }
+
+    #[test]
+    fn test_developer_role_is_read_as_system() {
+        let message: OpenAiMessage =
+            serde_json::from_value(json!({"role": "developer", "content": "Be brief."})).unwrap();
+
+        let converted = from_openai_message(&message).unwrap();
+        assert_eq!(converted.as_system().unwrap().content(), "Be brief.");
+    }
+
+    #[test]
+    fn test_refusal_part_is_kept_out_of_content() {
+        let message: OpenAiMessage = serde_json::from_value(json!({
+            "role": "assistant",
+            "content": [{"type": "refusal", "refusal": "I can't help with that."}]
+        }))
+        .unwrap();
+
+        let converted = from_openai_message(&message).unwrap();
+        let ai = converted.as_ai().unwrap();
+        assert_eq!(ai.content(), "");
+        assert_eq!(
+            ai.get_additional_kwarg::<String>("refusal").unwrap(),
+            Some("I can't help with that.".to_string())
+        );
+
+        let sent_back = to_openai_messages(&[converted]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&sent_back).unwrap()[0]["refusal"],
+            "I can't help with that."
+        );
+
+        let user: OpenAiMessage = serde_json::from_value(json!({
+            "role": "user",
+            "content": [{"type": "refusal", "refusal": "No."}]
+        }))
+        .unwrap();
+        assert!(matches!(
+            from_openai_message(&user).unwrap_err(),
+            ConversionError::UnsupportedContent { index: 0, .. }
+        ));
+    }
+}
@@ -13,14 +13,14 @@ macro_rules! define_message {
             }
 
             impl [<$message_type_enum Message>] {
-                pub fn new(content: &str) -> Self {
+                pub fn new(content: impl Into<MessageContent>) -> Self {
                     Self::new_with_example(content, false)
                 }
 
-                pub fn new_with_example(content: &str, example: bool) -> Self {
+                pub fn new_with_example(content: impl Into<MessageContent>, example: bool) -> Self {
                     Self {
                         base: BaseMessageFields {
-                            content: content.to_string(),
+                            content: content.into(),
                             example,
                             message_type: MessageType::$message_type_enum,
                             additional_kwargs: std::collections::HashMap::new(),
@@ -35,11 +35,11 @@ macro_rules! define_message {
                     self.base.example
                 }
 
-                pub fn additional_kwargs(&self) -> &std::collections::HashMap<String, String> {
+                pub fn additional_kwargs(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                     &self.base.additional_kwargs
                 }
 
-                pub fn response_metadata(&self) -> &std::collections::HashMap<String, String> {
+                pub fn response_metadata(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                     &self.base.response_metadata
                 }
 
@@ -51,8 +51,8 @@ macro_rules! define_message {
                     self.base.name.as_deref()
                 }
 
-                pub fn set_content(&mut self, new_content: &str) {
-                    self.base.content = new_content.to_string();
+                pub fn set_content(&mut self, new_content: impl Into<MessageContent>) {
+                    self.base.content = new_content.into();
                 }
 
                 pub fn set_example(&mut self, example: bool) {
@@ -69,7 +69,11 @@ macro_rules! define_message {
             }
 
             impl BaseMessage for [<$message_type_enum Message>] {
-                fn content(&self) -> &str {
+                fn content(&self) -> std::borrow::Cow<'_, str> {
+                    self.base.content.text()
+                }
+
+                fn message_content(&self) -> &MessageContent {
                     &self.base.content
                 }
 
@@ -85,14 +89,22 @@ macro_rules! define_message {
                     self.base.example
                 }
 
-                fn additional_kwargs(&self) -> &std::collections::HashMap<String, String> {
+                fn additional_kwargs(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                     &self.base.additional_kwargs
                 }
 
-                fn response_metadata(&self) -> &std::collections::HashMap<String, String> {
+                fn additional_kwargs_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+                    &mut self.base.additional_kwargs
+                }
+
+                fn response_metadata(&self) -> &std::collections::HashMap<String, serde_json::Value> {
                     &self.base.response_metadata
                 }
 
+                fn response_metadata_mut(&mut self) -> &mut std::collections::HashMap<String, serde_json::Value> {
+                    &mut self.base.response_metadata
+                }
+
                 fn id(&self) -> Option<&str> {
                     self.base.id.as_deref()
                 }
@@ -0,0 +1,315 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use crate::MessageEnum;
+
+use super::{ChatMessageHistory, HistoryError};
+
+const EXTENSION: &str = "jsonl";
+
+/// Stores each session as `<session_id>.jsonl` in a directory, one
+/// `MessageEnum` JSON object per line.
+///
+/// Each append is written with a single call to the end of the file. A line
+/// without a trailing newline is the remains of an interrupted write: reads
+/// skip it and the next append truncates it before writing.
+#[derive(Debug)]
+pub struct FileChatMessageHistory {
+    directory: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileChatMessageHistory {
+    /// Opens a store in `directory`, creating it if needed.
+    pub fn new(directory: impl Into<PathBuf>) -> Result<Self, HistoryError> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(FileChatMessageHistory {
+            directory,
+            lock: Mutex::new(()),
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn path(&self, session_id: &str) -> Result<PathBuf, HistoryError> {
+        validate_session_id(session_id)?;
+        Ok(self.directory.join(format!("{}.{}", session_id, EXTENSION)))
+    }
+
+    /// Reads a session one message at a time.
+    pub fn stream(&self, session_id: &str) -> Result<JsonlMessages, HistoryError> {
+        let reader = match File::open(self.path(session_id)?) {
+            Ok(file) => Some(BufReader::new(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        Ok(JsonlMessages {
+            reader,
+            line: 0,
+            buffer: Vec::new(),
+        })
+    }
+
+    pub fn session_ids(&self) -> Result<Vec<String>, HistoryError> {
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(EXTENSION) {
+                continue;
+            }
+            if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                ids.push(id.to_string());
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+impl ChatMessageHistory for FileChatMessageHistory {
+    fn append_many(
+        &self,
+        session_id: &str,
+        messages: Vec<MessageEnum>,
+    ) -> Result<(), HistoryError> {
+        let path = self.path(session_id)?;
+        let mut buffer = Vec::new();
+        for message in &messages {
+            serde_json::to_writer(&mut buffer, message)?;
+            buffer.push(b'\n');
+        }
+
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        truncate_partial_line(&mut file)?;
+        file.write_all(&buffer)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn messages(&self, session_id: &str) -> Result<Vec<MessageEnum>, HistoryError> {
+        self.stream(session_id)?.collect()
+    }
+
+    fn clear(&self, session_id: &str) -> Result<(), HistoryError> {
+        let path = self.path(session_id)?;
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Iterator over the messages of a JSONL session file.
+#[derive(Debug)]
+pub struct JsonlMessages {
+    reader: Option<BufReader<File>>,
+    line: usize,
+    buffer: Vec<u8>,
+}
+
+impl Iterator for JsonlMessages {
+    type Item = Result<MessageEnum, HistoryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let reader = self.reader.as_mut()?;
+            self.buffer.clear();
+            match reader.read_until(b'\n', &mut self.buffer) {
+                Ok(_) if self.buffer.last() != Some(&b'\n') => {
+                    self.reader = None;
+                    return None;
+                }
+                Ok(_) => self.line += 1,
+                Err(err) => {
+                    self.reader = None;
+                    return Some(Err(err.into()));
+                }
+            }
+
+            if self.buffer.trim_ascii().is_empty() {
+                continue;
+            }
+            return Some(serde_json::from_slice(&self.buffer).map_err(|source| {
+                HistoryError::InvalidRecord {
+                    line: self.line,
+                    source,
+                }
+            }));
+        }
+    }
+}
+
+fn validate_session_id(session_id: &str) -> Result<(), HistoryError> {
+    let valid = !session_id.is_empty()
+        && !session_id.starts_with('.')
+        && session_id
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(HistoryError::InvalidSessionId(session_id.to_string()))
+    }
+}
+
+/// Drops anything after the last newline, left behind by a write that did
+/// not complete.
+fn truncate_partial_line(file: &mut File) -> io::Result<()> {
+    let len = file.metadata()?.len();
+    let mut end = len;
+    let mut chunk = [0u8; 4096];
+
+    while end > 0 {
+        let start = end.saturating_sub(chunk.len() as u64);
+        let chunk = &mut chunk[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+
+        if let Some(newline) = chunk.iter().rposition(|&byte| byte == b'\n') {
+            let keep = start + newline as u64 + 1;
+            if keep < len {
+                file.set_len(keep)?;
+            }
+            return Ok(());
+        }
+        end = start;
+    }
+    file.set_len(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AiMessage, BaseMessage, ChatMessage, HumanMessage, ToolCall};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn store() -> (TempDir, FileChatMessageHistory) {
+        let directory = TempDir::new().unwrap();
+        let history = FileChatMessageHistory::new(directory.path().join("sessions")).unwrap();
+        (directory, history)
+    }
+
+    fn conversation() -> Vec<MessageEnum> {
+        vec![
+            MessageEnum::Human(HumanMessage::new("What's the weather?")),
+            MessageEnum::Ai(AiMessage::new_with_tool_calls(
+                "",
+                vec![ToolCall::new(
+                    "get_weather",
+                    json!({"city": "Paris"}),
+                    Some("call_1".to_string()),
+                )],
+            )),
+            MessageEnum::Chat(ChatMessage::new("Looks fine.", "critic".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip_one_file_per_session() {
+        let (_directory, history) = store();
+        history.append_many("alpha", conversation()).unwrap();
+        history
+            .append("beta", MessageEnum::Human(HumanMessage::new("Hi")))
+            .unwrap();
+        history
+            .append("alpha", MessageEnum::Ai(AiMessage::new("Sunny.")))
+            .unwrap();
+
+        let messages = history.messages("alpha").unwrap();
+        assert_eq!(&messages[..3], &conversation()[..]);
+        assert_eq!(messages[3].content(), "Sunny.");
+
+        assert_eq!(history.session_ids().unwrap(), vec!["alpha", "beta"]);
+        let contents = fs::read_to_string(history.path("alpha").unwrap()).unwrap();
+        assert_eq!(contents.lines().count(), 4);
+    }
+
+    #[test]
+    fn test_stream_reads_lazily() {
+        let (_directory, history) = store();
+        history.append_many("alpha", conversation()).unwrap();
+
+        let mut stream = history.stream("alpha").unwrap();
+        assert!(matches!(stream.next(), Some(Ok(MessageEnum::Human(_)))));
+        assert!(matches!(stream.next(), Some(Ok(MessageEnum::Ai(_)))));
+        assert!(matches!(stream.next(), Some(Ok(MessageEnum::Chat(_)))));
+        assert!(stream.next().is_none());
+
+        assert_eq!(history.stream("missing").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_recovers_from_truncated_tail() {
+        let (_directory, history) = store();
+        history.append_many("alpha", conversation()).unwrap();
+
+        let path = history.path("alpha").unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"role":"human","content":"Interr"#)
+            .unwrap();
+        drop(file);
+
+        assert_eq!(history.messages("alpha").unwrap(), conversation());
+
+        history
+            .append("alpha", MessageEnum::Ai(AiMessage::new("Sunny.")))
+            .unwrap();
+        let messages = history.messages("alpha").unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[3].content(), "Sunny.");
+    }
+
+    #[test]
+    fn test_truncated_only_line() {
+        let (_directory, history) = store();
+        fs::write(history.path("alpha").unwrap(), "{\"role\":").unwrap();
+
+        assert!(history.messages("alpha").unwrap().is_empty());
+        history
+            .append("alpha", MessageEnum::Human(HumanMessage::new("Hi")))
+            .unwrap();
+        assert_eq!(history.messages("alpha").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_corrupt_line_is_reported() {
+        let (_directory, history) = store();
+        fs::write(
+            history.path("alpha").unwrap(),
+            "{\"role\":\"human\",\"content\":\"Hi\"}\nnot json\n",
+        )
+        .unwrap();
+
+        let err = history.messages("alpha").unwrap_err();
+        assert!(matches!(err, HistoryError::InvalidRecord { line: 2, .. }));
+    }
+
+    #[test]
+    fn test_clear_and_invalid_session_ids() {
+        let (_directory, history) = store();
+        history.append_many("alpha", conversation()).unwrap();
+        history.clear("alpha").unwrap();
+        history.clear("alpha").unwrap();
+        assert!(history.messages("alpha").unwrap().is_empty());
+        assert!(history.session_ids().unwrap().is_empty());
+
+        for session_id in ["", "../escape", "a/b", ".hidden"] {
+            assert!(matches!(
+                history.append_many(session_id, conversation()),
+                Err(HistoryError::InvalidSessionId(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+use crate::MessageEnum;
+
+use super::{ChatMessageHistory, HistoryError};
+
+/// Keeps conversations in memory for the life of the process.
+#[derive(Debug, Default)]
+pub struct InMemoryChatMessageHistory {
+    sessions: RwLock<HashMap<String, Vec<MessageEnum>>>,
+}
+
+impl InMemoryChatMessageHistory {
+    pub fn new() -> Self {
+        InMemoryChatMessageHistory::default()
+    }
+
+    pub fn session_ids(&self) -> Vec<String> {
+        let sessions = self.sessions.read().unwrap_or_else(PoisonError::into_inner);
+        let mut ids: Vec<String> = sessions.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+}
+
+impl ChatMessageHistory for InMemoryChatMessageHistory {
+    fn append_many(
+        &self,
+        session_id: &str,
+        messages: Vec<MessageEnum>,
+    ) -> Result<(), HistoryError> {
+        let mut sessions = self
+            .sessions
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        sessions
+            .entry(session_id.to_string())
+            .or_default()
+            .extend(messages);
+        Ok(())
+    }
+
+    fn messages(&self, session_id: &str) -> Result<Vec<MessageEnum>, HistoryError> {
+        let sessions = self.sessions.read().unwrap_or_else(PoisonError::into_inner);
+        Ok(sessions.get(session_id).cloned().unwrap_or_default())
+    }
+
+    fn clear(&self, session_id: &str) -> Result<(), HistoryError> {
+        let mut sessions = self
+            .sessions
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        sessions.remove(session_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AiMessage, BaseMessage, HumanMessage};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_append_and_list() {
+        let history = InMemoryChatMessageHistory::new();
+        history
+            .append("a", MessageEnum::Human(HumanMessage::new("Hi")))
+            .unwrap();
+        history
+            .append_many(
+                "a",
+                vec![
+                    MessageEnum::Ai(AiMessage::new("Hello!")),
+                    MessageEnum::Human(HumanMessage::new("Bye")),
+                ],
+            )
+            .unwrap();
+        history
+            .append("b", MessageEnum::Human(HumanMessage::new("Other")))
+            .unwrap();
+
+        let messages = history.messages("a").unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1].content(), "Hello!");
+        assert_eq!(history.messages("b").unwrap().len(), 1);
+        assert!(history.messages("missing").unwrap().is_empty());
+        assert_eq!(history.session_ids(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_clear_only_affects_one_session() {
+        let history = InMemoryChatMessageHistory::new();
+        history
+            .append("a", MessageEnum::Human(HumanMessage::new("Hi")))
+            .unwrap();
+        history
+            .append("b", MessageEnum::Human(HumanMessage::new("Hi")))
+            .unwrap();
+
+        history.clear("a").unwrap();
+        history.clear("missing").unwrap();
+
+        assert!(history.messages("a").unwrap().is_empty());
+        assert_eq!(history.session_ids(), vec!["b"]);
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let history: Arc<dyn ChatMessageHistory> = Arc::new(InMemoryChatMessageHistory::new());
+
+        let handles: Vec<_> = (0..4)
+            .map(|worker| {
+                let history = Arc::clone(&history);
+                thread::spawn(move || {
+                    for turn in 0..25 {
+                        let content = format!("{}-{}", worker, turn);
+                        history
+                            .append("shared", MessageEnum::Human(HumanMessage::new(content)))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(history.messages("shared").unwrap().len(), 100);
+    }
+}
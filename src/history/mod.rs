@@ -0,0 +1,95 @@
+use std::fmt;
+use std::io;
+
+use crate::MessageEnum;
+
+pub mod file;
+pub mod memory;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use file::FileChatMessageHistory;
+pub use memory::InMemoryChatMessageHistory;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteChatMessageHistory;
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Io(io::Error),
+    Serialization(serde_json::Error),
+    InvalidRecord {
+        line: usize,
+        source: serde_json::Error,
+    },
+    InvalidSessionId(String),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Io(err) => write!(f, "History storage error: {}", err),
+            HistoryError::Serialization(err) => {
+                write!(f, "Failed to serialize history: {}", err)
+            }
+            HistoryError::InvalidRecord { line, source } => {
+                write!(f, "Invalid history record on line {}: {}", line, source)
+            }
+            HistoryError::InvalidSessionId(session_id) => {
+                write!(f, "Invalid session id {:?}", session_id)
+            }
+            #[cfg(feature = "sqlite")]
+            HistoryError::Sqlite(err) => write!(f, "SQLite error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HistoryError::Io(err) => Some(err),
+            HistoryError::Serialization(err) => Some(err),
+            HistoryError::InvalidRecord { source, .. } => Some(source),
+            HistoryError::InvalidSessionId(_) => None,
+            #[cfg(feature = "sqlite")]
+            HistoryError::Sqlite(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for HistoryError {
+    fn from(err: io::Error) -> Self {
+        HistoryError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for HistoryError {
+    fn from(err: serde_json::Error) -> Self {
+        HistoryError::Serialization(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for HistoryError {
+    fn from(err: rusqlite::Error) -> Self {
+        HistoryError::Sqlite(err)
+    }
+}
+
+/// Storage for conversations, keyed by session id.
+///
+/// Implementations are shared between threads, so every method takes
+/// `&self`. Reading a session that was never written returns no messages.
+pub trait ChatMessageHistory: Send + Sync {
+    fn append(&self, session_id: &str, message: MessageEnum) -> Result<(), HistoryError> {
+        self.append_many(session_id, vec![message])
+    }
+
+    fn append_many(&self, session_id: &str, messages: Vec<MessageEnum>)
+        -> Result<(), HistoryError>;
+
+    fn messages(&self, session_id: &str) -> Result<Vec<MessageEnum>, HistoryError>;
+
+    fn clear(&self, session_id: &str) -> Result<(), HistoryError>;
+}
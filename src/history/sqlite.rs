@@ -0,0 +1,335 @@
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::tool_message::ToolStatus;
+use crate::{BaseMessage, MessageEnum};
+
+use super::{ChatMessageHistory, HistoryError};
+
+/// Schema changes, applied in order. `PRAGMA user_version` records how many
+/// have run, so append new steps and never edit old ones.
+const MIGRATIONS: &[&str] = &["CREATE TABLE messages (
+        session_id TEXT NOT NULL,
+        seq INTEGER NOT NULL,
+        message_type TEXT NOT NULL,
+        role TEXT NOT NULL,
+        content TEXT NOT NULL,
+        name TEXT,
+        message_id TEXT,
+        tool_call_id TEXT,
+        tool_status TEXT,
+        tool_artifact TEXT,
+        payload TEXT NOT NULL,
+        PRIMARY KEY (session_id, seq)
+    );
+    CREATE INDEX messages_tool_call_id ON messages (tool_call_id)
+        WHERE tool_call_id IS NOT NULL;"];
+
+/// Stores conversations in a SQLite database, one row per message keyed by
+/// session id and sequence number.
+///
+/// Besides the full JSON payload, each row keeps the role, text content,
+/// name and id, and for tool messages the `tool_call_id`, status and
+/// artifact, so they can be queried with plain SQL.
+#[derive(Debug)]
+pub struct SqliteChatMessageHistory {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteChatMessageHistory {
+    /// Opens or creates the database at `path` and brings its schema up to
+    /// date.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, HistoryError> {
+        SqliteChatMessageHistory::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, HistoryError> {
+        SqliteChatMessageHistory::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut connection: Connection) -> Result<Self, HistoryError> {
+        migrate(&mut connection)?;
+        Ok(SqliteChatMessageHistory {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn len(&self, session_id: &str) -> Result<usize, HistoryError> {
+        let count: i64 = self.connection().query_row(
+            "SELECT COUNT(*) FROM messages WHERE session_id = ?1",
+            [session_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self, session_id: &str) -> Result<bool, HistoryError> {
+        Ok(self.len(session_id)? == 0)
+    }
+
+    /// Returns up to `limit` messages of a session, skipping the first
+    /// `offset`.
+    pub fn messages_page(
+        &self,
+        session_id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<MessageEnum>, HistoryError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare_cached(
+            "SELECT payload FROM messages WHERE session_id = ?1
+             ORDER BY seq LIMIT ?2 OFFSET ?3",
+        )?;
+        let payloads = statement
+            .query_map(params![session_id, limit as i64, offset as i64], |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        payloads
+            .iter()
+            .map(|payload| Ok(serde_json::from_str(payload)?))
+            .collect()
+    }
+
+    /// Finds the `ToolMessage` answering `tool_call_id` in a session.
+    pub fn tool_result(
+        &self,
+        session_id: &str,
+        tool_call_id: &str,
+    ) -> Result<Option<MessageEnum>, HistoryError> {
+        let payload: Option<String> = self
+            .connection()
+            .query_row(
+                "SELECT payload FROM messages WHERE session_id = ?1 AND tool_call_id = ?2
+                 ORDER BY seq LIMIT 1",
+                [session_id, tool_call_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()?)
+    }
+
+    pub fn session_ids(&self) -> Result<Vec<String>, HistoryError> {
+        let connection = self.connection();
+        let mut statement =
+            connection.prepare("SELECT DISTINCT session_id FROM messages ORDER BY session_id")?;
+        let ids = statement
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+}
+
+impl ChatMessageHistory for SqliteChatMessageHistory {
+    fn append_many(
+        &self,
+        session_id: &str,
+        messages: Vec<MessageEnum>,
+    ) -> Result<(), HistoryError> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        insert_messages(&transaction, session_id, &messages)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn messages(&self, session_id: &str) -> Result<Vec<MessageEnum>, HistoryError> {
+        self.messages_page(session_id, 0, i64::MAX as usize)
+    }
+
+    fn clear(&self, session_id: &str) -> Result<(), HistoryError> {
+        self.connection()
+            .execute("DELETE FROM messages WHERE session_id = ?1", [session_id])?;
+        Ok(())
+    }
+}
+
+fn migrate(connection: &mut Connection) -> Result<(), HistoryError> {
+    let transaction = connection.transaction()?;
+    let version: usize = transaction.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for migration in MIGRATIONS.iter().skip(version) {
+        transaction.execute_batch(migration)?;
+    }
+    if version < MIGRATIONS.len() {
+        transaction.pragma_update(None, "user_version", MIGRATIONS.len())?;
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+fn insert_messages(
+    transaction: &Transaction<'_>,
+    session_id: &str,
+    messages: &[MessageEnum],
+) -> Result<(), HistoryError> {
+    let next_seq: i64 = transaction.query_row(
+        "SELECT COALESCE(MAX(seq) + 1, 0) FROM messages WHERE session_id = ?1",
+        [session_id],
+        |row| row.get(0),
+    )?;
+    let mut statement = transaction.prepare_cached(
+        "INSERT INTO messages (
+            session_id, seq, message_type, role, content, name, message_id,
+            tool_call_id, tool_status, tool_artifact, payload
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+    )?;
+
+    for (seq, message) in (next_seq..).zip(messages) {
+        let tool = message.as_tool();
+        let tool_status = tool.map(|tool| match tool.status() {
+            ToolStatus::Success => "success",
+            ToolStatus::Error => "error",
+        });
+        statement.execute(params![
+            session_id,
+            seq,
+            message.message_type().as_str(),
+            message.role(),
+            message.content(),
+            message.name(),
+            message.id(),
+            tool.map(|tool| tool.tool_call_id()),
+            tool_status,
+            tool.and_then(|tool| tool.artifact().as_deref()),
+            serde_json::to_string(message)?,
+        ])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AiMessage, HumanMessage, ToolCall, ToolMessage};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn conversation() -> Vec<MessageEnum> {
+        vec![
+            MessageEnum::Human(HumanMessage::new("What's the weather?")),
+            MessageEnum::Ai(AiMessage::new_with_tool_calls(
+                "",
+                vec![ToolCall::new(
+                    "get_weather",
+                    json!({"city": "Paris"}),
+                    Some("call_1".to_string()),
+                )],
+            )),
+            MessageEnum::Tool(ToolMessage::new(
+                "Service unavailable",
+                "call_1".to_string(),
+                Some("trace-42".to_string()),
+                ToolStatus::Error,
+            )),
+            MessageEnum::Ai(AiMessage::new("I couldn't check the weather.")),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip_and_sequence() {
+        let history = SqliteChatMessageHistory::open_in_memory().unwrap();
+        history.append_many("alpha", conversation()).unwrap();
+        history
+            .append("beta", MessageEnum::Human(HumanMessage::new("Hi")))
+            .unwrap();
+        history
+            .append("alpha", MessageEnum::Human(HumanMessage::new("Thanks")))
+            .unwrap();
+
+        let messages = history.messages("alpha").unwrap();
+        assert_eq!(&messages[..4], &conversation()[..]);
+        assert_eq!(messages[4].content(), "Thanks");
+        assert_eq!(history.len("alpha").unwrap(), 5);
+        assert_eq!(history.session_ids().unwrap(), vec!["alpha", "beta"]);
+        assert!(history.messages("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_paging() {
+        let history = SqliteChatMessageHistory::open_in_memory().unwrap();
+        history.append_many("alpha", conversation()).unwrap();
+
+        let page = history.messages_page("alpha", 1, 2).unwrap();
+        assert_eq!(page, &conversation()[1..3]);
+        assert_eq!(history.messages_page("alpha", 3, 10).unwrap().len(), 1);
+        assert!(history.messages_page("alpha", 4, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tool_fields_are_queryable() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("history.db");
+        let history = SqliteChatMessageHistory::open(&path).unwrap();
+        history.append_many("alpha", conversation()).unwrap();
+
+        let tool = history.tool_result("alpha", "call_1").unwrap().unwrap();
+        assert_eq!(tool, conversation()[2]);
+        assert!(history.tool_result("alpha", "call_2").unwrap().is_none());
+
+        let connection = Connection::open(&path).unwrap();
+        let row: (i64, String, String, String) = connection
+            .query_row(
+                "SELECT seq, tool_call_id, tool_status, tool_artifact FROM messages
+                 WHERE session_id = 'alpha' AND role = 'tool'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                2,
+                "call_1".to_string(),
+                "error".to_string(),
+                "trace-42".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_reopen_keeps_data_and_schema_version() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("history.db");
+
+        SqliteChatMessageHistory::open(&path)
+            .unwrap()
+            .append_many("alpha", conversation())
+            .unwrap();
+
+        let history = SqliteChatMessageHistory::open(&path).unwrap();
+        assert_eq!(history.len("alpha").unwrap(), 4);
+
+        let version: usize = history
+            .connection()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_clear() {
+        let history = SqliteChatMessageHistory::open_in_memory().unwrap();
+        history.append_many("alpha", conversation()).unwrap();
+        history.append_many("beta", conversation()).unwrap();
+
+        history.clear("alpha").unwrap();
+        assert!(history.is_empty("alpha").unwrap());
+        assert_eq!(history.len("beta").unwrap(), 4);
+
+        history
+            .append("alpha", MessageEnum::Human(HumanMessage::new("Again")))
+            .unwrap();
+        assert_eq!(history.messages("alpha").unwrap()[0].content(), "Again");
+    }
+}
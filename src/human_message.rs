@@ -46,11 +46,11 @@ mod tests {
         human_message
             .base
             .additional_kwargs
-            .insert("mood".to_string(), "curious".to_string());
+            .insert("mood".to_string(), "curious".into());
 
         assert_eq!(
             human_message.additional_kwargs().get("mood"),
-            Some(&"curious".to_string())
+            Some(&"curious".into())
         );
     }
 
@@ -60,11 +60,11 @@ mod tests {
         human_message
             .base
             .response_metadata
-            .insert("source".to_string(), "User".to_string());
+            .insert("source".to_string(), "User".into());
 
         assert_eq!(
             human_message.response_metadata().get("source"),
-            Some(&"User".to_string())
+            Some(&"User".into())
         );
     }
 
@@ -110,21 +110,21 @@ mod tests {
         human_message
             .base
             .additional_kwargs
-            .insert("intent".to_string(), "query".to_string());
+            .insert("intent".to_string(), "query".into());
         human_message
             .base
             .response_metadata
-            .insert("platform".to_string(), "mobile".to_string());
+            .insert("platform".to_string(), "mobile".into());
 
         assert_eq!(human_message.id(), Some("HUM123"));
         assert_eq!(human_message.name(), Some("User123"));
         assert_eq!(
             human_message.additional_kwargs().get("intent"),
-            Some(&"query".to_string())
+            Some(&"query".into())
         );
         assert_eq!(
             human_message.response_metadata().get("platform"),
-            Some(&"mobile".to_string())
+            Some(&"mobile".into())
         );
 
         let expected_json = json!({
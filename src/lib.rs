@@ -1,7 +1,14 @@
+// Lets code generated by `derive_base_message` name `::messageforge` paths
+// from inside this crate as well as from dependents.
+extern crate self as messageforge;
+
 pub mod message_type;
 pub use message_type::InvalidMessageTypeError;
 pub use message_type::MessageType;
 
+pub mod message_content;
+pub use message_content::{ContentBlock, MediaSource, MessageContent};
+
 pub mod base_message;
 pub use base_message::BaseMessage;
 pub use base_message::BaseMessageFields;
@@ -10,6 +17,12 @@ pub mod define_message;
 pub mod prelude;
 pub use derive_base_message;
 
+pub mod tool_call;
+pub use tool_call::{InvalidToolCall, ToolCall};
+
+pub mod usage_metadata;
+pub use usage_metadata::{InputTokenDetails, OutputTokenDetails, UsageMetadata};
+
 pub mod ai_message;
 pub use ai_message::AiMessage;
 
@@ -19,6 +32,9 @@ pub use chat_message::ChatMessage;
 pub mod human_message;
 pub use human_message::HumanMessage;
 
+pub mod remove_message;
+pub use remove_message::RemoveMessage;
+
 pub mod system_message;
 pub use system_message::SystemMessage;
 
@@ -27,3 +43,24 @@ pub use tool_message::ToolMessage;
 
 pub mod message_enum;
 pub use message_enum::MessageEnum;
+
+pub mod message_chunk;
+pub use message_chunk::{
+    AiMessageChunk, ChatMessageChunk, ChunkMergeError, HumanMessageChunk, SystemMessageChunk,
+    ToolCallChunk, ToolMessageChunk,
+};
+
+pub mod convert;
+pub use convert::ConversionError;
+
+pub mod history;
+pub use history::ChatMessageHistory;
+
+pub mod prompts;
+pub use prompts::{ChatPromptTemplate, PromptTemplate};
+
+pub mod tokens;
+pub use tokens::TokenCounter;
+
+pub mod utils;
+pub use utils::add_messages;
@@ -0,0 +1,667 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, AddAssign};
+
+use crate::prelude::*;
+use crate::tool_call::{InvalidToolCall, ToolCall};
+use crate::tool_message::{ToolMessage, ToolStatus};
+use crate::usage_metadata::UsageMetadata;
+use crate::MessageEnum;
+use derive_base_message::BaseMessage;
+use serde_json::{Map, Value};
+
+/// Metadata keys whose streamed string values identify the response rather
+/// than carry text, so the first value is kept instead of concatenated.
+const NON_CONCATENATED_KEYS: &[&str] = &["id", "type", "index", "model_name", "model_provider"];
+
+/// Returned by `try_merge` when two chunks belong to different messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkMergeError {
+    ToolCallIdMismatch { left: String, right: String },
+    RoleMismatch { left: String, right: String },
+}
+
+impl fmt::Display for ChunkMergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkMergeError::ToolCallIdMismatch { left, right } => write!(
+                f,
+                "Cannot merge ToolMessageChunks with different tool_call_id values: {} and {}",
+                left, right
+            ),
+            ChunkMergeError::RoleMismatch { left, right } => write!(
+                f,
+                "Cannot merge ChatMessageChunks with different roles: {} and {}",
+                left, right
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChunkMergeError {}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename = "tool_call_chunk")]
+pub struct ToolCallChunk {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub args: Option<String>,
+
+    #[serde(default)]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    pub index: Option<usize>,
+}
+
+#[derive(BaseMessage, Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AiMessageChunk {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[base_message(default)]
+    pub tool_call_chunks: Vec<ToolCallChunk>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[base_message(default)]
+    pub usage_metadata: Option<UsageMetadata>,
+
+    #[serde(flatten)]
+    pub base: BaseMessageFields,
+}
+
+#[derive(BaseMessage, Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HumanMessageChunk {
+    #[serde(flatten)]
+    pub base: BaseMessageFields,
+}
+
+#[derive(BaseMessage, Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SystemMessageChunk {
+    #[serde(flatten)]
+    pub base: BaseMessageFields,
+}
+
+#[derive(BaseMessage, Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ToolMessageChunk {
+    tool_call_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    artifact: Option<String>,
+
+    status: ToolStatus,
+
+    #[serde(flatten)]
+    base: BaseMessageFields,
+}
+
+#[derive(BaseMessage, Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChatMessageChunk {
+    role: String,
+
+    #[serde(flatten)]
+    base: BaseMessageFields,
+}
+
+impl ToolCallChunk {
+    pub fn new(
+        name: Option<String>,
+        args: Option<String>,
+        id: Option<String>,
+        index: Option<usize>,
+    ) -> Self {
+        ToolCallChunk {
+            name,
+            args,
+            id,
+            index,
+        }
+    }
+
+    fn merge(&mut self, other: ToolCallChunk) {
+        concat_option(&mut self.name, other.name);
+        concat_option(&mut self.args, other.args);
+        if self.id.is_none() {
+            self.id = other.id;
+        }
+    }
+
+    fn into_tool_call(self) -> Result<ToolCall, InvalidToolCall> {
+        ToolCall::try_from_raw(self.name, self.args, self.id)
+    }
+}
+
+impl AiMessageChunk {
+    pub fn new_with_tool_call_chunks(
+        content: impl Into<MessageContent>,
+        tool_call_chunks: Vec<ToolCallChunk>,
+    ) -> Self {
+        let mut chunk = Self::new(content);
+        chunk.tool_call_chunks = tool_call_chunks;
+        chunk
+    }
+
+    pub fn tool_call_chunks(&self) -> &[ToolCallChunk] {
+        &self.tool_call_chunks
+    }
+}
+
+impl ToolMessageChunk {
+    pub fn tool_call_id(&self) -> &str {
+        &self.tool_call_id
+    }
+
+    pub fn artifact(&self) -> &Option<String> {
+        &self.artifact
+    }
+
+    pub fn status(&self) -> &ToolStatus {
+        &self.status
+    }
+
+    /// Appends `other`, leaving `self` unchanged if the chunks answer
+    /// different tool calls.
+    pub fn try_merge(&mut self, other: ToolMessageChunk) -> Result<(), ChunkMergeError> {
+        if self.tool_call_id != other.tool_call_id {
+            return Err(ChunkMergeError::ToolCallIdMismatch {
+                left: self.tool_call_id.clone(),
+                right: other.tool_call_id,
+            });
+        }
+        if other.status == ToolStatus::Error {
+            self.status = ToolStatus::Error;
+        }
+        if self.artifact.is_none() {
+            self.artifact = other.artifact;
+        }
+        merge_base(&mut self.base, other.base);
+        Ok(())
+    }
+}
+
+impl ChatMessageChunk {
+    /// Appends `other`, leaving `self` unchanged if the chunks have different
+    /// roles.
+    pub fn try_merge(&mut self, other: ChatMessageChunk) -> Result<(), ChunkMergeError> {
+        if self.role != other.role {
+            return Err(ChunkMergeError::RoleMismatch {
+                left: self.role.clone(),
+                right: other.role,
+            });
+        }
+        merge_base(&mut self.base, other.base);
+        Ok(())
+    }
+}
+
+fn concat_option(left: &mut Option<String>, right: Option<String>) {
+    match (left.as_mut(), right) {
+        (Some(left), Some(right)) => left.push_str(&right),
+        (None, right) => *left = right,
+        (Some(_), None) => {}
+    }
+}
+
+fn merge_values(key: &str, left: &mut Value, right: Value) {
+    match (left, right) {
+        (_, Value::Null) => {}
+        (left @ Value::Null, right) => *left = right,
+        (Value::String(left), Value::String(right)) => {
+            if !NON_CONCATENATED_KEYS.contains(&key) {
+                left.push_str(&right);
+            }
+        }
+        (Value::Object(left), Value::Object(right)) => merge_objects(left, right),
+        (Value::Array(left), Value::Array(right)) => left.extend(right),
+        (left, right) => *left = right,
+    }
+}
+
+fn merge_objects(left: &mut Map<String, Value>, right: Map<String, Value>) {
+    for (key, value) in right {
+        match left.get_mut(&key) {
+            Some(existing) => merge_values(&key, existing, value),
+            None => {
+                left.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_maps(left: &mut HashMap<String, Value>, right: HashMap<String, Value>) {
+    for (key, value) in right {
+        match left.get_mut(&key) {
+            Some(existing) => merge_values(&key, existing, value),
+            None => {
+                left.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_base(left: &mut BaseMessageFields, right: BaseMessageFields) {
+    left.content += right.content;
+    merge_maps(&mut left.additional_kwargs, right.additional_kwargs);
+    merge_maps(&mut left.response_metadata, right.response_metadata);
+    if left.id.is_none() {
+        left.id = right.id;
+    }
+    if left.name.is_none() {
+        left.name = right.name;
+    }
+}
+
+fn merge_tool_call_chunks(left: &mut Vec<ToolCallChunk>, right: Vec<ToolCallChunk>) {
+    for chunk in right {
+        let existing = chunk
+            .index
+            .and_then(|index| left.iter_mut().find(|c| c.index == Some(index)));
+        match existing {
+            Some(existing) => existing.merge(chunk),
+            None => left.push(chunk),
+        }
+    }
+}
+
+impl AddAssign for AiMessageChunk {
+    fn add_assign(&mut self, other: AiMessageChunk) {
+        merge_tool_call_chunks(&mut self.tool_call_chunks, other.tool_call_chunks);
+        self.usage_metadata = match (self.usage_metadata, other.usage_metadata) {
+            (Some(left), Some(right)) => Some(left + right),
+            (left, right) => left.or(right),
+        };
+        merge_base(&mut self.base, other.base);
+    }
+}
+
+impl AddAssign for HumanMessageChunk {
+    fn add_assign(&mut self, other: HumanMessageChunk) {
+        merge_base(&mut self.base, other.base);
+    }
+}
+
+impl AddAssign for SystemMessageChunk {
+    fn add_assign(&mut self, other: SystemMessageChunk) {
+        merge_base(&mut self.base, other.base);
+    }
+}
+
+impl AddAssign for ToolMessageChunk {
+    /// # Panics
+    ///
+    /// Panics if the chunks answer different tool calls. Use
+    /// [`ToolMessageChunk::try_merge`] for chunks from untrusted streams.
+    fn add_assign(&mut self, other: ToolMessageChunk) {
+        if let Err(err) = self.try_merge(other) {
+            panic!("{}", err);
+        }
+    }
+}
+
+impl AddAssign for ChatMessageChunk {
+    /// # Panics
+    ///
+    /// Panics if the chunks have different roles. Use
+    /// [`ChatMessageChunk::try_merge`] for chunks from untrusted streams.
+    fn add_assign(&mut self, other: ChatMessageChunk) {
+        if let Err(err) = self.try_merge(other) {
+            panic!("{}", err);
+        }
+    }
+}
+
+macro_rules! impl_chunk_add {
+    ($($chunk:ident),*) => {
+        $(
+            impl Add for $chunk {
+                type Output = $chunk;
+
+                /// Panics under the same conditions as `+=`.
+                fn add(mut self, other: $chunk) -> $chunk {
+                    self += other;
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_chunk_add!(
+    AiMessageChunk,
+    HumanMessageChunk,
+    SystemMessageChunk,
+    ToolMessageChunk,
+    ChatMessageChunk
+);
+
+impl From<AiMessageChunk> for AiMessage {
+    fn from(chunk: AiMessageChunk) -> Self {
+        let mut tool_calls = Vec::new();
+        let mut invalid_tool_calls = Vec::new();
+        for tool_call_chunk in chunk.tool_call_chunks {
+            match tool_call_chunk.into_tool_call() {
+                Ok(tool_call) => tool_calls.push(tool_call),
+                Err(invalid_tool_call) => invalid_tool_calls.push(invalid_tool_call),
+            }
+        }
+
+        AiMessage {
+            tool_calls,
+            invalid_tool_calls,
+            usage_metadata: chunk.usage_metadata,
+            base: chunk.base,
+        }
+    }
+}
+
+impl From<HumanMessageChunk> for HumanMessage {
+    fn from(chunk: HumanMessageChunk) -> Self {
+        HumanMessage { base: chunk.base }
+    }
+}
+
+impl From<SystemMessageChunk> for SystemMessage {
+    fn from(chunk: SystemMessageChunk) -> Self {
+        SystemMessage { base: chunk.base }
+    }
+}
+
+impl From<ToolMessageChunk> for ToolMessage {
+    fn from(chunk: ToolMessageChunk) -> Self {
+        ToolMessage::new_with_base(chunk.tool_call_id, chunk.artifact, chunk.status, chunk.base)
+    }
+}
+
+impl From<ChatMessageChunk> for ChatMessage {
+    fn from(chunk: ChatMessageChunk) -> Self {
+        ChatMessage::new_with_base(chunk.role, chunk.base)
+    }
+}
+
+impl From<AiMessageChunk> for MessageEnum {
+    fn from(chunk: AiMessageChunk) -> Self {
+        MessageEnum::Ai(chunk.into())
+    }
+}
+
+impl From<HumanMessageChunk> for MessageEnum {
+    fn from(chunk: HumanMessageChunk) -> Self {
+        MessageEnum::Human(chunk.into())
+    }
+}
+
+impl From<SystemMessageChunk> for MessageEnum {
+    fn from(chunk: SystemMessageChunk) -> Self {
+        MessageEnum::System(chunk.into())
+    }
+}
+
+impl From<ToolMessageChunk> for MessageEnum {
+    fn from(chunk: ToolMessageChunk) -> Self {
+        MessageEnum::Tool(chunk.into())
+    }
+}
+
+impl From<ChatMessageChunk> for MessageEnum {
+    fn from(chunk: ChatMessageChunk) -> Self {
+        MessageEnum::Chat(chunk.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ai_chunk_content_merge() {
+        let merged = AiMessageChunk::new("Hel")
+            + AiMessageChunk::new("lo, ")
+            + AiMessageChunk::new("world!");
+
+        assert_eq!(merged.content(), "Hello, world!");
+        assert_eq!(merged.message_type(), &MessageType::Ai);
+    }
+
+    #[test]
+    fn test_ai_chunk_add_assign_merges_metadata() {
+        let mut first = AiMessageChunk::new("Hi");
+        first.set_id(Some("run-1".to_string()));
+        first
+            .base
+            .response_metadata
+            .insert("model_name".to_string(), "gpt-4o".into());
+        first
+            .base
+            .additional_kwargs
+            .insert("refusal".to_string(), "I ".into());
+
+        let mut second = AiMessageChunk::new(" there");
+        second.set_id(Some("run-2".to_string()));
+        second
+            .base
+            .response_metadata
+            .insert("model_name".to_string(), "gpt-4o".into());
+        second
+            .base
+            .response_metadata
+            .insert("finish_reason".to_string(), "stop".into());
+        second
+            .base
+            .additional_kwargs
+            .insert("refusal".to_string(), "can't".into());
+
+        first += second;
+
+        assert_eq!(first.content(), "Hi there");
+        assert_eq!(first.id(), Some("run-1"));
+        assert_eq!(
+            first.response_metadata().get("model_name"),
+            Some(&"gpt-4o".into())
+        );
+        assert_eq!(
+            first.response_metadata().get("finish_reason"),
+            Some(&"stop".into())
+        );
+        assert_eq!(
+            first.additional_kwargs().get("refusal"),
+            Some(&"I can't".into())
+        );
+    }
+
+    #[test]
+    fn test_ai_chunk_merges_nested_metadata() {
+        let mut first = AiMessageChunk::new("");
+        first
+            .insert_response_metadata("logprobs", json!({"content": [{"token": "Hi"}]}))
+            .unwrap();
+        let mut second = AiMessageChunk::new("");
+        second
+            .insert_response_metadata("logprobs", json!({"content": [{"token": "!"}]}))
+            .unwrap();
+        second
+            .insert_response_metadata("system_fingerprint", "fp_1")
+            .unwrap();
+
+        let merged = first + second;
+        assert_eq!(
+            merged.response_metadata()["logprobs"],
+            json!({"content": [{"token": "Hi"}, {"token": "!"}]})
+        );
+        assert_eq!(merged.response_metadata()["system_fingerprint"], "fp_1");
+    }
+
+    #[test]
+    fn test_tool_call_chunks_merge_by_index() {
+        let first = AiMessageChunk::new_with_tool_call_chunks(
+            "",
+            vec![ToolCallChunk::new(
+                Some("get_weather".to_string()),
+                Some("{\"ci".to_string()),
+                Some("call_1".to_string()),
+                Some(0),
+            )],
+        );
+        let second = AiMessageChunk::new_with_tool_call_chunks(
+            "",
+            vec![
+                ToolCallChunk::new(None, Some("ty\": \"Paris\"}".to_string()), None, Some(0)),
+                ToolCallChunk::new(
+                    Some("get_time".to_string()),
+                    Some("{}".to_string()),
+                    Some("call_2".to_string()),
+                    Some(1),
+                ),
+            ],
+        );
+
+        let merged = first + second;
+        assert_eq!(merged.tool_call_chunks().len(), 2);
+        assert_eq!(
+            merged.tool_call_chunks()[0].args.as_deref(),
+            Some("{\"city\": \"Paris\"}")
+        );
+        assert_eq!(merged.tool_call_chunks()[0].id.as_deref(), Some("call_1"));
+
+        let message = AiMessage::from(merged);
+        assert_eq!(message.tool_calls().len(), 2);
+        assert_eq!(message.tool_calls()[0].name(), "get_weather");
+        assert_eq!(message.tool_calls()[0].args(), &json!({"city": "Paris"}));
+        assert_eq!(message.tool_calls()[1].args(), &json!({}));
+        assert!(message.invalid_tool_calls().is_empty());
+    }
+
+    #[test]
+    fn test_unparseable_tool_call_chunk_becomes_invalid() {
+        let chunk = AiMessageChunk::new_with_tool_call_chunks(
+            "",
+            vec![ToolCallChunk::new(
+                Some("get_weather".to_string()),
+                Some("{\"city\":".to_string()),
+                Some("call_1".to_string()),
+                Some(0),
+            )],
+        );
+
+        let message = AiMessage::from(chunk);
+        assert!(message.tool_calls().is_empty());
+        assert_eq!(message.invalid_tool_calls().len(), 1);
+        assert_eq!(
+            message.invalid_tool_calls()[0].args.as_deref(),
+            Some("{\"city\":")
+        );
+        assert!(message.invalid_tool_calls()[0].error.is_some());
+    }
+
+    #[test]
+    fn test_ai_chunk_usage_metadata_is_summed() {
+        let mut first = AiMessageChunk::new("Hi");
+        first.usage_metadata = Some(UsageMetadata::new(10, 1));
+        let mut second = AiMessageChunk::new("!");
+        second.usage_metadata = Some(UsageMetadata::new(0, 1));
+        let third = AiMessageChunk::new("");
+
+        let message = AiMessage::from(first + second + third);
+        assert_eq!(message.usage_metadata(), Some(&UsageMetadata::new(10, 2)));
+    }
+
+    #[test]
+    fn test_chunk_into_message_enum() {
+        let merged = HumanMessageChunk::new("Hello") + HumanMessageChunk::new(" there");
+        let message: MessageEnum = merged.into();
+
+        assert_eq!(message.content(), "Hello there");
+        assert!(message.as_human().is_some());
+    }
+
+    #[test]
+    fn test_tool_chunk_merge() {
+        let first = ToolMessageChunk::new("par", "call_1".to_string(), None, ToolStatus::Success);
+        let second = ToolMessageChunk::new(
+            "tial",
+            "call_1".to_string(),
+            Some("artifact".to_string()),
+            ToolStatus::Error,
+        );
+
+        let message = ToolMessage::from(first + second);
+        assert_eq!(message.content(), "partial");
+        assert_eq!(message.tool_call_id(), "call_1");
+        assert_eq!(message.artifact().as_deref(), Some("artifact"));
+        assert_eq!(message.status(), &ToolStatus::Error);
+    }
+
+    #[test]
+    #[should_panic(expected = "different tool_call_id")]
+    fn test_tool_chunk_merge_mismatched_ids() {
+        let _ = ToolMessageChunk::new("a", "call_1".to_string(), None, ToolStatus::Success)
+            + ToolMessageChunk::new("b", "call_2".to_string(), None, ToolStatus::Success);
+    }
+
+    #[test]
+    fn test_chat_chunk_merge() {
+        let merged = ChatMessageChunk::new("Ahoy", "pirate".to_string())
+            + ChatMessageChunk::new(" matey", "pirate".to_string());
+
+        let message = ChatMessage::from(merged);
+        assert_eq!(message.content(), "Ahoy matey");
+        assert_eq!(message.role(), "pirate");
+    }
+
+    #[test]
+    fn test_tool_chunk_try_merge() {
+        let mut chunk = ToolMessageChunk::new("a", "call_1".to_string(), None, ToolStatus::Success);
+        chunk
+            .try_merge(ToolMessageChunk::new(
+                "b",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Success,
+            ))
+            .unwrap();
+        assert_eq!(chunk.content(), "ab");
+
+        let err = chunk
+            .try_merge(ToolMessageChunk::new(
+                "c",
+                "call_2".to_string(),
+                None,
+                ToolStatus::Error,
+            ))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ChunkMergeError::ToolCallIdMismatch {
+                left: "call_1".to_string(),
+                right: "call_2".to_string()
+            }
+        );
+        assert_eq!(chunk.content(), "ab");
+        assert_eq!(chunk.status(), &ToolStatus::Success);
+    }
+
+    #[test]
+    fn test_chat_chunk_try_merge() {
+        let mut chunk = ChatMessageChunk::new("Ahoy", "pirate".to_string());
+        chunk
+            .try_merge(ChatMessageChunk::new(" matey", "pirate".to_string()))
+            .unwrap();
+        assert_eq!(chunk.content(), "Ahoy matey");
+
+        let err = chunk
+            .try_merge(ChatMessageChunk::new("Arr", "parrot".to_string()))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot merge ChatMessageChunks with different roles: pirate and parrot"
+        );
+        assert_eq!(chunk.content(), "Ahoy matey");
+    }
+
+    #[test]
+    #[should_panic(expected = "different roles")]
+    fn test_chat_chunk_merge_mismatched_roles() {
+        let _ = ChatMessageChunk::new("Ahoy", "pirate".to_string())
+            + ChatMessageChunk::new("Arr", "parrot".to_string());
+    }
+}
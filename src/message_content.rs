@@ -0,0 +1,367 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::AddAssign;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Blocks(Vec<ContentBlock>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        #[serde(flatten)]
+        source: MediaSource,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        detail: Option<String>,
+    },
+    Audio {
+        #[serde(flatten)]
+        source: MediaSource,
+    },
+    File {
+        #[serde(flatten)]
+        source: MediaSource,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        filename: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "source_type", rename_all = "snake_case")]
+pub enum MediaSource {
+    Url { url: String },
+    Base64 { mime_type: String, data: String },
+    Id { id: String },
+}
+
+impl MessageContent {
+    /// Returns the textual view of the content. Text blocks are concatenated
+    /// in order and non-text blocks are skipped.
+    pub fn text(&self) -> Cow<'_, str> {
+        match self {
+            MessageContent::Text(text) => Cow::Borrowed(text),
+            MessageContent::Blocks(blocks) => {
+                let mut texts = blocks.iter().filter_map(ContentBlock::as_text);
+                match (texts.next(), texts.next()) {
+                    (None, _) => Cow::Borrowed(""),
+                    (Some(first), None) => Cow::Borrowed(first),
+                    (Some(first), Some(second)) => {
+                        let mut joined = String::from(first);
+                        joined.push_str(second);
+                        texts.for_each(|text| joined.push_str(text));
+                        Cow::Owned(joined)
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            MessageContent::Text(text) => text.is_empty(),
+            MessageContent::Blocks(blocks) => blocks.is_empty(),
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self, MessageContent::Text(_))
+    }
+
+    pub fn blocks(&self) -> Cow<'_, [ContentBlock]> {
+        match self {
+            MessageContent::Text(text) if text.is_empty() => Cow::Borrowed(&[]),
+            MessageContent::Text(text) => Cow::Owned(vec![ContentBlock::text(text.as_str())]),
+            MessageContent::Blocks(blocks) => Cow::Borrowed(blocks),
+        }
+    }
+
+    pub fn push(&mut self, block: ContentBlock) {
+        self.blocks_mut().push(block);
+    }
+
+    fn blocks_mut(&mut self) -> &mut Vec<ContentBlock> {
+        if let MessageContent::Text(text) = self {
+            let text = std::mem::take(text);
+            *self = MessageContent::Blocks(if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![ContentBlock::text(text)]
+            });
+        }
+        match self {
+            MessageContent::Blocks(blocks) => blocks,
+            MessageContent::Text(_) => unreachable!("text content was converted to blocks"),
+        }
+    }
+}
+
+impl ContentBlock {
+    pub fn text(text: impl Into<String>) -> Self {
+        ContentBlock::Text { text: text.into() }
+    }
+
+    pub fn image_url(url: impl Into<String>) -> Self {
+        ContentBlock::Image {
+            source: MediaSource::Url { url: url.into() },
+            detail: None,
+        }
+    }
+
+    pub fn image_base64(mime_type: impl Into<String>, data: impl Into<String>) -> Self {
+        ContentBlock::Image {
+            source: MediaSource::Base64 {
+                mime_type: mime_type.into(),
+                data: data.into(),
+            },
+            detail: None,
+        }
+    }
+
+    pub fn audio_base64(mime_type: impl Into<String>, data: impl Into<String>) -> Self {
+        ContentBlock::Audio {
+            source: MediaSource::Base64 {
+                mime_type: mime_type.into(),
+                data: data.into(),
+            },
+        }
+    }
+
+    pub fn file_id(id: impl Into<String>, filename: Option<String>) -> Self {
+        ContentBlock::File {
+            source: MediaSource::Id { id: id.into() },
+            filename,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        if let ContentBlock::Text { text } = self {
+            Some(text)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for MessageContent {
+    fn default() -> Self {
+        MessageContent::Text(String::new())
+    }
+}
+
+/// Appends streamed content. Two text contents are concatenated; otherwise
+/// the right-hand blocks are appended, joining text that meets at the seam.
+impl AddAssign for MessageContent {
+    fn add_assign(&mut self, other: MessageContent) {
+        let right_blocks = match (&mut *self, other) {
+            (MessageContent::Text(left), MessageContent::Text(right)) => {
+                left.push_str(&right);
+                return;
+            }
+            (_, MessageContent::Blocks(right)) => right,
+            (_, MessageContent::Text(right)) if right.is_empty() => return,
+            (_, MessageContent::Text(right)) => vec![ContentBlock::text(right)],
+        };
+
+        let left_blocks = self.blocks_mut();
+
+        let mut right_blocks = right_blocks.into_iter();
+        if let Some(first) = right_blocks.next() {
+            match (left_blocks.last_mut(), first) {
+                (Some(ContentBlock::Text { text: left }), ContentBlock::Text { text: right }) => {
+                    left.push_str(&right)
+                }
+                (_, first) => left_blocks.push(first),
+            }
+        }
+        left_blocks.extend(right_blocks);
+    }
+}
+
+impl fmt::Debug for MessageContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageContent::Text(text) => fmt::Debug::fmt(text, f),
+            MessageContent::Blocks(blocks) => fmt::Debug::fmt(blocks, f),
+        }
+    }
+}
+
+impl fmt::Display for MessageContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text())
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        MessageContent::Text(text.to_string())
+    }
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Text(text)
+    }
+}
+
+impl From<&String> for MessageContent {
+    fn from(text: &String) -> Self {
+        MessageContent::Text(text.clone())
+    }
+}
+
+impl From<Vec<ContentBlock>> for MessageContent {
+    fn from(blocks: Vec<ContentBlock>) -> Self {
+        MessageContent::Blocks(blocks)
+    }
+}
+
+/// Content equals a string only when it is plain text or a single text block.
+/// Compare against `text()` to ignore non-text blocks.
+impl PartialEq<str> for MessageContent {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            MessageContent::Text(text) => text == other,
+            MessageContent::Blocks(blocks) => {
+                matches!(blocks.as_slice(), [ContentBlock::Text { text }] if text == other)
+            }
+        }
+    }
+}
+
+impl PartialEq<&str> for MessageContent {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_text_content_serializes_as_bare_string() {
+        let content = MessageContent::from("Hello");
+        assert_eq!(serde_json::to_value(&content).unwrap(), json!("Hello"));
+    }
+
+    #[test]
+    fn test_deserialize_bare_string() {
+        let content: MessageContent = serde_json::from_value(json!("Hello")).unwrap();
+        assert_eq!(content, MessageContent::Text("Hello".to_string()));
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let json_data = json!([
+            {"type": "text", "text": "What is in this image?"},
+            {"type": "image", "source_type": "url", "url": "https://example.com/cat.png", "detail": "high"},
+            {"type": "audio", "source_type": "base64", "mime_type": "audio/wav", "data": "UklGRg=="},
+            {"type": "file", "source_type": "id", "id": "file_123", "filename": "report.pdf"}
+        ]);
+
+        let content: MessageContent = serde_json::from_value(json_data.clone()).unwrap();
+        let MessageContent::Blocks(ref blocks) = content else {
+            panic!("Expected block content");
+        };
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(
+            blocks[1],
+            ContentBlock::Image {
+                source: MediaSource::Url {
+                    url: "https://example.com/cat.png".to_string()
+                },
+                detail: Some("high".to_string()),
+            }
+        );
+        assert_eq!(
+            blocks[3],
+            ContentBlock::file_id("file_123", Some("report.pdf".to_string()))
+        );
+
+        assert_eq!(serde_json::to_value(&content).unwrap(), json_data);
+    }
+
+    #[test]
+    fn test_text_view_joins_text_blocks() {
+        let content = MessageContent::from(vec![
+            ContentBlock::text("Hello, "),
+            ContentBlock::image_url("https://example.com/cat.png"),
+            ContentBlock::text("world!"),
+        ]);
+        assert_eq!(content.text(), "Hello, world!");
+        assert_ne!(content, "Hello, world!");
+
+        let single = MessageContent::from(vec![ContentBlock::text("only")]);
+        assert!(matches!(single.text(), Cow::Borrowed("only")));
+
+        let empty = MessageContent::from(vec![ContentBlock::image_url("u")]);
+        assert_eq!(empty.text(), "");
+    }
+
+    #[test]
+    fn test_str_equality_requires_text_only_content() {
+        assert_eq!(MessageContent::from("hi"), "hi");
+        assert_eq!(MessageContent::from(vec![ContentBlock::text("hi")]), "hi");
+
+        let with_image = MessageContent::from(vec![
+            ContentBlock::text("hi"),
+            ContentBlock::image_url("https://example.com/cat.png"),
+        ]);
+        assert_ne!(with_image, "hi");
+        assert!(with_image != *"hi");
+        assert_eq!(with_image.text(), "hi");
+    }
+
+    #[test]
+    fn test_push_converts_text_to_blocks() {
+        let mut content = MessageContent::from("Describe this:");
+        content.push(ContentBlock::image_base64("image/png", "iVBORw0KGgo="));
+
+        assert!(!content.is_text());
+        assert_eq!(content.blocks().len(), 2);
+        assert_eq!(content.text(), "Describe this:");
+    }
+
+    #[test]
+    fn test_debug_format_matches_string() {
+        let content = MessageContent::from("Debug me");
+        assert_eq!(format!("{:?}", content), r#""Debug me""#);
+    }
+
+    #[test]
+    fn test_add_assign_text() {
+        let mut content = MessageContent::from("Hel");
+        content += MessageContent::from("lo");
+        assert_eq!(content, MessageContent::Text("Hello".to_string()));
+    }
+
+    #[test]
+    fn test_add_assign_blocks_joins_text_at_seam() {
+        let mut content = MessageContent::from("Look at ");
+        content += MessageContent::from(vec![
+            ContentBlock::text("this"),
+            ContentBlock::image_url("https://example.com/cat.png"),
+        ]);
+        content += MessageContent::from(" cat");
+
+        assert_eq!(
+            content,
+            MessageContent::Blocks(vec![
+                ContentBlock::text("Look at this"),
+                ContentBlock::image_url("https://example.com/cat.png"),
+                ContentBlock::text(" cat"),
+            ])
+        );
+    }
+}
@@ -1,12 +1,16 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
 
 use crate::tool_message::ToolStatus;
 use crate::{
-    AiMessage, BaseMessageFields, HumanMessage, InvalidMessageTypeError, SystemMessage, ToolMessage,
+    AiMessage, BaseMessageFields, ChatMessage, HumanMessage, InvalidMessageTypeError,
+    InvalidToolCall, MessageContent, RemoveMessage, SystemMessage, ToolCall, ToolMessage,
+    UsageMetadata,
 };
 use crate::{BaseMessage, MessageType};
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 #[derive(Serialize, Clone, PartialEq)]
 #[serde(tag = "role", rename_all = "lowercase")]
@@ -15,6 +19,9 @@ pub enum MessageEnum {
     Human(HumanMessage),
     System(SystemMessage),
     Tool(ToolMessage),
+    Remove(RemoveMessage),
+    #[serde(untagged)]
+    Chat(ChatMessage),
 }
 
 impl MessageEnum {
@@ -50,6 +57,22 @@ impl MessageEnum {
         }
     }
 
+    pub fn as_remove(&self) -> Option<&RemoveMessage> {
+        if let MessageEnum::Remove(ref message) = self {
+            Some(message)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_chat(&self) -> Option<&ChatMessage> {
+        if let MessageEnum::Chat(ref message) = self {
+            Some(message)
+        } else {
+            None
+        }
+    }
+
     pub fn human_from(input: &str) -> Result<HumanMessage, InvalidMessageTypeError> {
         match MessageEnum::try_from(input)? {
             MessageEnum::Human(human_message) => Ok(human_message),
@@ -90,6 +113,16 @@ impl MessageEnum {
         }
     }
 
+    pub fn chat_from(input: &str) -> Result<ChatMessage, InvalidMessageTypeError> {
+        match MessageEnum::try_from(input)? {
+            MessageEnum::Chat(chat_message) => Ok(chat_message),
+            _ => Err(InvalidMessageTypeError::new(format!(
+                "Expected a ChatMessage, got a different type: {}",
+                input
+            ))),
+        }
+    }
+
     fn parse_tool_message(content: &str) -> Result<Self, InvalidMessageTypeError> {
         let tool_parts: Vec<&str> = content.splitn(2, ": ").collect();
         if tool_parts.len() == 2 {
@@ -109,6 +142,12 @@ impl MessageEnum {
         }
     }
 
+    /// Parses one `role: content` message per line. Use
+    /// `utils::TranscriptParser` for content that spans several lines.
+    ///
+    /// Roles other than `human`, `ai`, `system` and `tool` are read as
+    /// `ChatMessage`s, so a misspelt role such as `assistant` is not an
+    /// error. Use [`MessageEnum::parse_messages_strict`] to reject them.
     pub fn parse_messages(input: &str) -> Result<Vec<MessageEnum>, InvalidMessageTypeError> {
         input
             .lines()
@@ -116,15 +155,64 @@ impl MessageEnum {
             .map(MessageEnum::try_from)
             .collect()
     }
+
+    /// Like [`MessageEnum::parse_messages`], but fails on any role other
+    /// than `human`, `ai`, `system` and `tool` instead of reading it as a
+    /// `ChatMessage`.
+    pub fn parse_messages_strict(input: &str) -> Result<Vec<MessageEnum>, InvalidMessageTypeError> {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| MessageEnum::parse_line(line, true))
+            .collect()
+    }
+
+    fn parse_line(value: &str, strict: bool) -> Result<Self, InvalidMessageTypeError> {
+        let parts: Vec<&str> = value.splitn(2, ": ").collect();
+
+        if parts.len() != 2 {
+            return Err(InvalidMessageTypeError::new(format!(
+                "Invalid message format: {}",
+                value
+            )));
+        }
+
+        let (role_part, content) = (parts[0], parts[1]);
+
+        match role_part.to_lowercase().as_str() {
+            "human" => Ok(MessageEnum::Human(HumanMessage::new(content))),
+            "ai" => Ok(MessageEnum::Ai(AiMessage::new(content))),
+            "system" => Ok(MessageEnum::System(SystemMessage::new(content))),
+            "tool" => Self::parse_tool_message(content),
+            _ if strict => Err(InvalidMessageTypeError::new(value.to_string())),
+            _ => Ok(MessageEnum::Chat(ChatMessage::new(
+                content,
+                role_part.to_string(),
+            ))),
+        }
+    }
 }
 
 impl BaseMessage for MessageEnum {
-    fn content(&self) -> &str {
+    fn content(&self) -> Cow<'_, str> {
         match self {
             MessageEnum::Ai(message) => message.content(),
             MessageEnum::Human(message) => message.content(),
             MessageEnum::System(message) => message.content(),
             MessageEnum::Tool(message) => message.content(),
+            MessageEnum::Remove(message) => message.content(),
+            MessageEnum::Chat(message) => message.content(),
+        }
+    }
+
+    fn message_content(&self) -> &MessageContent {
+        match self {
+            MessageEnum::Ai(message) => message.message_content(),
+            MessageEnum::Human(message) => message.message_content(),
+            MessageEnum::System(message) => message.message_content(),
+            MessageEnum::Tool(message) => message.message_content(),
+            MessageEnum::Remove(message) => message.message_content(),
+            MessageEnum::Chat(message) => message.message_content(),
         }
     }
 
@@ -134,6 +222,8 @@ impl BaseMessage for MessageEnum {
             MessageEnum::Human(message) => message.message_type(),
             MessageEnum::System(message) => message.message_type(),
             MessageEnum::Tool(message) => message.message_type(),
+            MessageEnum::Remove(message) => message.message_type(),
+            MessageEnum::Chat(message) => message.message_type(),
         }
     }
 
@@ -143,6 +233,8 @@ impl BaseMessage for MessageEnum {
             MessageEnum::Human(_) => "human",
             MessageEnum::System(_) => "system",
             MessageEnum::Tool(_) => "tool",
+            MessageEnum::Remove(_) => "remove",
+            MessageEnum::Chat(message) => message.role(),
         }
     }
 
@@ -152,6 +244,8 @@ impl BaseMessage for MessageEnum {
             MessageEnum::Human(message) => message.name(),
             MessageEnum::System(message) => message.name(),
             MessageEnum::Tool(message) => message.name(),
+            MessageEnum::Remove(message) => message.name(),
+            MessageEnum::Chat(message) => message.name(),
         }
     }
 
@@ -161,24 +255,52 @@ impl BaseMessage for MessageEnum {
             MessageEnum::Human(message) => message.is_example(),
             MessageEnum::System(message) => message.is_example(),
             MessageEnum::Tool(message) => message.is_example(),
+            MessageEnum::Remove(message) => message.is_example(),
+            MessageEnum::Chat(message) => message.is_example(),
         }
     }
 
-    fn additional_kwargs(&self) -> &HashMap<String, String> {
+    fn additional_kwargs(&self) -> &HashMap<String, Value> {
         match self {
             MessageEnum::Ai(message) => message.additional_kwargs(),
             MessageEnum::Human(message) => message.additional_kwargs(),
             MessageEnum::System(message) => message.additional_kwargs(),
             MessageEnum::Tool(message) => message.additional_kwargs(),
+            MessageEnum::Remove(message) => message.additional_kwargs(),
+            MessageEnum::Chat(message) => message.additional_kwargs(),
+        }
+    }
+
+    fn additional_kwargs_mut(&mut self) -> &mut HashMap<String, Value> {
+        match self {
+            MessageEnum::Ai(message) => message.additional_kwargs_mut(),
+            MessageEnum::Human(message) => message.additional_kwargs_mut(),
+            MessageEnum::System(message) => message.additional_kwargs_mut(),
+            MessageEnum::Tool(message) => message.additional_kwargs_mut(),
+            MessageEnum::Remove(message) => message.additional_kwargs_mut(),
+            MessageEnum::Chat(message) => message.additional_kwargs_mut(),
         }
     }
 
-    fn response_metadata(&self) -> &HashMap<String, String> {
+    fn response_metadata(&self) -> &HashMap<String, Value> {
         match self {
             MessageEnum::Ai(message) => message.response_metadata(),
             MessageEnum::Human(message) => message.response_metadata(),
             MessageEnum::System(message) => message.response_metadata(),
             MessageEnum::Tool(message) => message.response_metadata(),
+            MessageEnum::Remove(message) => message.response_metadata(),
+            MessageEnum::Chat(message) => message.response_metadata(),
+        }
+    }
+
+    fn response_metadata_mut(&mut self) -> &mut HashMap<String, Value> {
+        match self {
+            MessageEnum::Ai(message) => message.response_metadata_mut(),
+            MessageEnum::Human(message) => message.response_metadata_mut(),
+            MessageEnum::System(message) => message.response_metadata_mut(),
+            MessageEnum::Tool(message) => message.response_metadata_mut(),
+            MessageEnum::Remove(message) => message.response_metadata_mut(),
+            MessageEnum::Chat(message) => message.response_metadata_mut(),
         }
     }
 
@@ -188,6 +310,8 @@ impl BaseMessage for MessageEnum {
             MessageEnum::Human(message) => message.id(),
             MessageEnum::System(message) => message.id(),
             MessageEnum::Tool(message) => message.id(),
+            MessageEnum::Remove(message) => message.id(),
+            MessageEnum::Chat(message) => message.id(),
         }
     }
 }
@@ -199,6 +323,8 @@ impl fmt::Debug for MessageEnum {
             MessageEnum::Human(message) => write!(f, "HumanMessage({:?})", message),
             MessageEnum::System(message) => write!(f, "SystemMessage({:?})", message),
             MessageEnum::Tool(message) => write!(f, "ToolMessage({:?})", message),
+            MessageEnum::Remove(message) => write!(f, "RemoveMessage({:?})", message),
+            MessageEnum::Chat(message) => write!(f, "ChatMessage({:?})", message),
         }
     }
 }
@@ -227,6 +353,21 @@ impl From<ToolMessage> for MessageEnum {
     }
 }
 
+impl From<RemoveMessage> for MessageEnum {
+    fn from(message: RemoveMessage) -> Self {
+        MessageEnum::Remove(message)
+    }
+}
+
+impl From<ChatMessage> for MessageEnum {
+    fn from(message: ChatMessage) -> Self {
+        MessageEnum::Chat(message)
+    }
+}
+
+/// Messages whose `role` is not one of the standard message types
+/// deserialize as [`MessageEnum::Chat`] rather than failing with
+/// "Unsupported message type"; check [`MessageEnum::as_chat`] to reject them.
 impl<'de> Deserialize<'de> for MessageEnum {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -235,17 +376,27 @@ impl<'de> Deserialize<'de> for MessageEnum {
         #[derive(Deserialize)]
         struct TempMessage {
             role: String,
-            content: String,
+            content: MessageContent,
             #[serde(default)]
             example: bool,
             #[serde(default)]
-            additional_kwargs: HashMap<String, String>,
+            additional_kwargs: HashMap<String, Value>,
             #[serde(default)]
-            response_metadata: HashMap<String, String>,
+            response_metadata: HashMap<String, Value>,
             #[serde(default)]
             id: Option<String>,
             #[serde(default)]
             name: Option<String>,
+            #[serde(default)]
+            message_type: Option<MessageType>,
+
+            // AiMessage specific fields
+            #[serde(default)]
+            tool_calls: Vec<ToolCall>,
+            #[serde(default)]
+            invalid_tool_calls: Vec<InvalidToolCall>,
+            #[serde(default)]
+            usage_metadata: Option<UsageMetadata>,
 
             // ToolMessage specific fields
             #[serde(default)]
@@ -257,8 +408,12 @@ impl<'de> Deserialize<'de> for MessageEnum {
         }
 
         let temp = TempMessage::deserialize(deserializer)?;
-        let message_type =
-            MessageType::try_from(temp.role.as_str()).map_err(serde::de::Error::custom)?;
+        // Roles that are not one of the standard message types belong to a
+        // ChatMessage, as does anything explicitly tagged as one.
+        let message_type = match temp.message_type {
+            Some(MessageType::Chat) => MessageType::Chat,
+            _ => MessageType::try_from(temp.role.as_str()).unwrap_or(MessageType::Chat),
+        };
 
         let base = BaseMessageFields {
             content: temp.content,
@@ -271,7 +426,12 @@ impl<'de> Deserialize<'de> for MessageEnum {
         };
 
         match message_type {
-            MessageType::Ai => Ok(MessageEnum::Ai(AiMessage { base })),
+            MessageType::Ai => Ok(MessageEnum::Ai(AiMessage {
+                tool_calls: temp.tool_calls,
+                invalid_tool_calls: temp.invalid_tool_calls,
+                usage_metadata: temp.usage_metadata,
+                base,
+            })),
             MessageType::Human => Ok(MessageEnum::Human(HumanMessage { base })),
             MessageType::System => Ok(MessageEnum::System(SystemMessage { base })),
             MessageType::Tool => {
@@ -288,42 +448,32 @@ impl<'de> Deserialize<'de> for MessageEnum {
                     base,
                 )))
             }
-            _ => Err(serde::de::Error::custom("Unsupported message type")),
+            MessageType::Remove => {
+                if base.id.is_none() {
+                    return Err(serde::de::Error::custom("Missing id for RemoveMessage"));
+                }
+                Ok(MessageEnum::Remove(RemoveMessage { base }))
+            }
+            MessageType::Chat => Ok(MessageEnum::Chat(ChatMessage::new_with_base(
+                temp.role, base,
+            ))),
         }
     }
 }
 
+/// Parses a single `role: content` line. Unknown roles become
+/// [`MessageEnum::Chat`]; see [`MessageEnum::parse_messages_strict`].
 impl TryFrom<&str> for MessageEnum {
     type Error = InvalidMessageTypeError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let parts: Vec<&str> = value.splitn(2, ": ").collect();
-
-        if parts.len() != 2 {
-            return Err(InvalidMessageTypeError::new(format!(
-                "Invalid message format: {}",
-                value
-            )));
-        }
-
-        let (role_part, content) = (parts[0], parts[1]);
-
-        match role_part.to_lowercase().as_str() {
-            "human" => Ok(MessageEnum::Human(HumanMessage::new(content))),
-            "ai" => Ok(MessageEnum::Ai(AiMessage::new(content))),
-            "system" => Ok(MessageEnum::System(SystemMessage::new(content))),
-            "tool" => Self::parse_tool_message(content),
-            _ => Err(InvalidMessageTypeError::new(format!(
-                "Invalid message type: {}",
-                value
-            ))),
-        }
+        MessageEnum::parse_line(value, false)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::BaseMessageFields;
+    use crate::{BaseMessageFields, ContentBlock};
 
     use super::*;
     use serde_json::{json, Value};
@@ -331,8 +481,11 @@ mod tests {
     #[test]
     fn test_message_enum_serialization_ai_message() {
         let ai_message = AiMessage {
+            tool_calls: Vec::new(),
+            invalid_tool_calls: Vec::new(),
+            usage_metadata: None,
             base: BaseMessageFields {
-                content: "Hello from AI.".to_string(),
+                content: "Hello from AI.".into(),
                 example: false,
                 message_type: MessageType::Ai,
                 additional_kwargs: HashMap::new(),
@@ -360,7 +513,7 @@ mod tests {
     fn test_message_enum_serialization_human_message() {
         let human_message = HumanMessage {
             base: BaseMessageFields {
-                content: "Hello from Human.".to_string(),
+                content: "Hello from Human.".into(),
                 example: false,
                 message_type: MessageType::Human,
                 additional_kwargs: HashMap::new(),
@@ -388,7 +541,7 @@ mod tests {
     fn test_message_enum_serialization_system_message() {
         let system_message = SystemMessage {
             base: BaseMessageFields {
-                content: "This is a system message.".to_string(),
+                content: "This is a system message.".into(),
                 example: false,
                 message_type: MessageType::System,
                 additional_kwargs: HashMap::new(),
@@ -415,7 +568,7 @@ mod tests {
     #[test]
     fn test_message_enum_serialization_tool_message() {
         let base = BaseMessageFields {
-            content: "Tool message content".to_string(),
+            content: "Tool message content".into(),
             example: false,
             message_type: MessageType::Tool,
             additional_kwargs: HashMap::new(),
@@ -765,11 +918,137 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_message_enum_multimodal_content_round_trip() {
+        let json_data = json!({
+            "role": "human",
+            "content": [
+                {"type": "text", "text": "What is in this image?"},
+                {"type": "image", "source_type": "url", "url": "https://example.com/cat.png"}
+            ],
+            "example": false,
+            "message_type": "Human"
+        });
+
+        let message_enum: MessageEnum = serde_json::from_value(json_data.clone()).unwrap();
+        assert_eq!(message_enum.content(), "What is in this image?");
+        assert_eq!(message_enum.message_content().blocks().len(), 2);
+        assert_eq!(
+            message_enum.message_content().blocks()[1],
+            ContentBlock::image_url("https://example.com/cat.png")
+        );
+
+        let serialized = serde_json::to_value(&message_enum).unwrap();
+        assert_eq!(serialized, json_data);
+    }
+
+    #[test]
+    fn test_message_enum_ai_tool_calls_round_trip() {
+        let json_data = json!({
+            "role": "ai",
+            "tool_calls": [
+                {"type": "tool_call", "name": "get_weather", "args": {"city": "Paris"}, "id": "call_1"}
+            ],
+            "invalid_tool_calls": [
+                {"type": "invalid_tool_call", "name": "get_time", "args": "{", "id": "call_2", "error": "EOF"}
+            ],
+            "usage_metadata": {"input_tokens": 15, "output_tokens": 9, "total_tokens": 24},
+            "content": "",
+            "example": false,
+            "message_type": "Ai"
+        });
+
+        let message_enum: MessageEnum = serde_json::from_value(json_data.clone()).unwrap();
+        let ai_message = message_enum.as_ai().expect("Expected AiMessage");
+        assert_eq!(ai_message.tool_calls().len(), 1);
+        assert_eq!(ai_message.tool_calls()[0].name(), "get_weather");
+        assert_eq!(ai_message.tool_calls()[0].id(), Some("call_1"));
+        assert_eq!(
+            ai_message.invalid_tool_calls()[0].error.as_deref(),
+            Some("EOF")
+        );
+        assert_eq!(
+            ai_message.usage_metadata(),
+            Some(&UsageMetadata::new(15, 9))
+        );
+
+        let serialized = serde_json::to_value(&message_enum).unwrap();
+        assert_eq!(serialized, json_data);
+    }
+
+    #[test]
+    fn test_message_enum_nested_metadata() {
+        let json_data = json!({
+            "role": "ai",
+            "content": "Hello.",
+            "additional_kwargs": {
+                "function_call": {"name": "lookup", "arguments": "{}"}
+            },
+            "response_metadata": {
+                "logprobs": [{"token": "Hello", "logprob": -0.1}],
+                "model_name": "gpt-4o"
+            }
+        });
+
+        let message_enum: MessageEnum = serde_json::from_value(json_data).unwrap();
+        assert_eq!(
+            message_enum.additional_kwargs()["function_call"]["name"],
+            "lookup"
+        );
+        assert_eq!(
+            message_enum
+                .get_response_metadata::<String>("model_name")
+                .unwrap(),
+            Some("gpt-4o".to_string())
+        );
+        assert_eq!(
+            message_enum
+                .get_response_metadata::<u32>("missing")
+                .unwrap(),
+            None
+        );
+        assert!(message_enum
+            .get_response_metadata::<u32>("model_name")
+            .is_err());
+    }
+
+    #[test]
+    fn test_message_enum_typed_metadata_insert() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct SafetyRating {
+            category: String,
+            probability: String,
+        }
+
+        let rating = SafetyRating {
+            category: "HARM_CATEGORY_HARASSMENT".to_string(),
+            probability: "NEGLIGIBLE".to_string(),
+        };
+
+        let mut message_enum = MessageEnum::Ai(AiMessage::new("Hello."));
+        message_enum
+            .insert_response_metadata("safety_ratings", vec![&rating])
+            .unwrap();
+        message_enum
+            .insert_additional_kwarg("refusal", Option::<String>::None)
+            .unwrap();
+
+        let ratings: Vec<SafetyRating> = message_enum
+            .get_response_metadata("safety_ratings")
+            .unwrap()
+            .unwrap();
+        assert_eq!(ratings, vec![rating]);
+        assert_eq!(message_enum.additional_kwargs()["refusal"], Value::Null);
+    }
+
     #[test]
     fn test_message_enum_serialization_with_message_type() {
         let ai_message = AiMessage {
+            tool_calls: Vec::new(),
+            invalid_tool_calls: Vec::new(),
+            usage_metadata: None,
             base: BaseMessageFields {
-                content: "Hello from AI.".to_string(),
+                content: "Hello from AI.".into(),
                 example: false,
                 additional_kwargs: HashMap::new(),
                 response_metadata: HashMap::new(),
@@ -797,7 +1076,7 @@ mod tests {
     fn test_as_human() {
         let human_message = HumanMessage {
             base: BaseMessageFields {
-                content: "Hello from Human.".to_string(),
+                content: "Hello from Human.".into(),
                 example: false,
                 message_type: MessageType::Human,
                 additional_kwargs: HashMap::new(),
@@ -820,8 +1099,11 @@ mod tests {
     #[test]
     fn test_as_ai() {
         let ai_message = AiMessage {
+            tool_calls: Vec::new(),
+            invalid_tool_calls: Vec::new(),
+            usage_metadata: None,
             base: BaseMessageFields {
-                content: "Hello from AI.".to_string(),
+                content: "Hello from AI.".into(),
                 example: false,
                 message_type: MessageType::Ai,
                 additional_kwargs: HashMap::new(),
@@ -845,7 +1127,7 @@ mod tests {
     fn test_as_system() {
         let system_message = SystemMessage {
             base: BaseMessageFields {
-                content: "This is a system message.".to_string(),
+                content: "This is a system message.".into(),
                 example: false,
                 message_type: MessageType::System,
                 additional_kwargs: HashMap::new(),
@@ -899,7 +1181,7 @@ mod tests {
     fn test_mixed_message_enum() {
         let human_message = HumanMessage {
             base: BaseMessageFields {
-                content: "Hello from Human.".to_string(),
+                content: "Hello from Human.".into(),
                 example: false,
                 message_type: MessageType::Human,
                 additional_kwargs: HashMap::new(),
@@ -911,7 +1193,7 @@ mod tests {
 
         let system_message = SystemMessage {
             base: BaseMessageFields {
-                content: "System message.".to_string(),
+                content: "System message.".into(),
                 example: false,
                 message_type: MessageType::System,
                 additional_kwargs: HashMap::new(),
@@ -922,8 +1204,11 @@ mod tests {
         };
 
         let ai_message = AiMessage {
+            tool_calls: Vec::new(),
+            invalid_tool_calls: Vec::new(),
+            usage_metadata: None,
             base: BaseMessageFields {
-                content: "Hello from AI.".to_string(),
+                content: "Hello from AI.".into(),
                 example: false,
                 message_type: MessageType::Ai,
                 additional_kwargs: HashMap::new(),
@@ -1205,22 +1490,120 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_unknown_role_as_chat() {
+        let input = "Moderator: What is 2+2?\nai: 4";
+
+        let messages = MessageEnum::parse_messages(input).unwrap();
+        assert_eq!(messages.len(), 2);
+
+        let chat_message = messages[0].as_chat().unwrap();
+        assert_eq!(chat_message.role(), "Moderator");
+        assert_eq!(chat_message.content(), "What is 2+2?");
+        assert_eq!(messages[0].role(), "Moderator");
+    }
+
+    #[test]
+    fn test_parse_messages_strict_rejects_unknown_roles() {
+        let input = "human: What is 2+2?\nassistant: 4";
+
+        let messages = MessageEnum::parse_messages(input).unwrap();
+        assert_eq!(messages[1].as_chat().unwrap().role(), "assistant");
+
+        let err = MessageEnum::parse_messages_strict(input).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid message type: assistant: 4");
+
+        let messages = MessageEnum::parse_messages_strict("Human: Hi\ntool: call_1: 4").unwrap();
+        assert!(messages[0].as_human().is_some());
+        assert!(messages[1].as_tool().is_some());
+        assert!(MessageEnum::parse_messages_strict("human: Hi\nno separator").is_err());
+    }
+
     #[test]
     fn test_parse_invalid_message_format() {
-        let input = "unknown: What is 2+2?\nai: 4";
+        let input = "human: What is 2+2?\ninvalid format\nai: 4";
 
         let result = MessageEnum::parse_messages(input);
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_parse_mixed_valid_and_invalid_messages() {
-        let input = "human: What is 2+2?\ninvalid: format\nai: 4";
+    fn test_chat_from() {
+        let chat_message = MessageEnum::chat_from("critic: Needs more detail.").unwrap();
+        assert_eq!(chat_message.role(), "critic");
 
-        let result = MessageEnum::parse_messages(input);
+        let err = MessageEnum::chat_from("Human: Hello from Human.").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid message type: Expected a ChatMessage, got a different type: Human: Hello from Human."
+        );
+    }
+
+    #[test]
+    fn test_message_enum_chat_round_trip() {
+        let mut chat_message = ChatMessage::new("Looks good to me.", "reviewer".to_string());
+        chat_message.set_id(Some("chat_1".to_string()));
+        let message_enum = MessageEnum::Chat(chat_message);
+
+        let serialized = serde_json::to_value(&message_enum).unwrap();
+        assert_eq!(
+            serialized,
+            json!({
+                "role": "reviewer",
+                "content": "Looks good to me.",
+                "example": false,
+                "message_type": "Chat",
+                "id": "chat_1"
+            })
+        );
+
+        let deserialized: MessageEnum = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, message_enum);
+    }
+
+    #[test]
+    fn test_message_enum_chat_with_standard_role_name() {
+        let message_enum = MessageEnum::Chat(ChatMessage::new("Hi", "human".to_string()));
+
+        let serialized = serde_json::to_string(&message_enum).unwrap();
+        let deserialized: MessageEnum = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, message_enum);
+    }
+
+    #[test]
+    fn test_message_enum_remove_round_trip() {
+        let message_enum = MessageEnum::Remove(RemoveMessage::with_id("msg_1"));
+
+        let serialized = serde_json::to_value(&message_enum).unwrap();
+        assert_eq!(serialized["role"], "remove");
+
+        let deserialized: MessageEnum = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, message_enum);
+    }
+
+    #[test]
+    fn test_message_enum_remove_requires_id() {
+        let json_data = r#"{"role": "remove", "content": ""}"#;
+
+        let result = serde_json::from_str::<MessageEnum>(json_data);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_deserialize_mixed_conversation_with_custom_roles() {
+        let json_data = r#"[
+            {"role": "human", "content": "Draft a haiku.", "message_type": "Human"},
+            {"role": "critic", "content": "Too long."},
+            {"role": "ai", "content": "Shorter now.", "message_type": "Ai"}
+        ]"#;
+
+        let messages: Vec<MessageEnum> = serde_json::from_str(json_data).unwrap();
+        assert!(matches!(messages[0], MessageEnum::Human(_)));
+        assert_eq!(messages[1].as_chat().unwrap().role(), "critic");
+        assert_eq!(messages[1].message_type(), &MessageType::Chat);
+        assert!(matches!(messages[2], MessageEnum::Ai(_)));
+    }
+
     #[test]
     fn test_parse_only_valid_messages() {
         let input = "human: What is 2+2?\nai: 4";
@@ -6,6 +6,7 @@ pub enum MessageType {
     Ai,
     Chat,
     Human,
+    Remove,
     System,
     Tool,
 }
@@ -16,6 +17,7 @@ impl MessageType {
             MessageType::Ai => "ai",
             MessageType::Chat => "chat",
             MessageType::Human => "human",
+            MessageType::Remove => "remove",
             MessageType::System => "system",
             MessageType::Tool => "tool",
         }
@@ -49,6 +51,7 @@ impl TryFrom<&str> for MessageType {
             "system" | "System" | "SystemMessage" => Ok(MessageType::System),
             "chat" | "Chat" | "ChatMessage" => Ok(MessageType::Chat),
             "tool" | "Tool" | "ToolMessage" => Ok(MessageType::Tool),
+            "remove" | "Remove" | "RemoveMessage" => Ok(MessageType::Remove),
             _ => Err(InvalidMessageTypeError::new(format!(
                 "Invalid message type: {}",
                 s
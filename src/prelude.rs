@@ -1,8 +1,10 @@
 pub use crate::base_message::{BaseMessage, BaseMessageFields};
+pub use crate::message_content::{ContentBlock, MediaSource, MessageContent};
 pub use crate::message_type::MessageType::*;
 pub use crate::message_type::{InvalidMessageTypeError, MessageType};
 
 pub use serde::{Deserialize, Serialize};
+pub use serde_json;
 
 pub use crate::define_message;
 pub use crate::derive_base_message;
@@ -10,5 +12,7 @@ pub use crate::derive_base_message;
 pub use crate::ai_message::AiMessage;
 pub use crate::chat_message::ChatMessage;
 pub use crate::human_message::HumanMessage;
+pub use crate::remove_message::RemoveMessage;
 pub use crate::system_message::SystemMessage;
+pub use crate::tool_call::{InvalidToolCall, ToolCall};
 // pub use crate::tool_message::ToolMessage;
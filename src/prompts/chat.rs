@@ -0,0 +1,491 @@
+use std::collections::BTreeSet;
+
+use super::{PromptError, PromptTemplate, PromptValues};
+use crate::{AiMessage, ChatMessage, HumanMessage, MessageEnum, SystemMessage};
+
+/// Splices a list of messages, such as the conversation so far, into a chat
+/// prompt at render time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessagesPlaceholder {
+    variable_name: String,
+    optional: bool,
+    n_messages: Option<usize>,
+}
+
+impl MessagesPlaceholder {
+    pub fn new(variable_name: impl Into<String>) -> Self {
+        MessagesPlaceholder {
+            variable_name: variable_name.into(),
+            optional: false,
+            n_messages: None,
+        }
+    }
+
+    pub fn new_optional(variable_name: impl Into<String>) -> Self {
+        MessagesPlaceholder {
+            optional: true,
+            ..MessagesPlaceholder::new(variable_name)
+        }
+    }
+
+    pub fn variable_name(&self) -> &str {
+        &self.variable_name
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    pub fn n_messages(&self) -> Option<usize> {
+        self.n_messages
+    }
+
+    pub fn set_optional(&mut self, optional: bool) {
+        self.optional = optional;
+    }
+
+    /// Limits the placeholder to the last `n_messages` messages supplied.
+    pub fn set_n_messages(&mut self, n_messages: Option<usize>) {
+        self.n_messages = n_messages;
+    }
+
+    fn render(&self, values: &PromptValues) -> Result<Vec<MessageEnum>, PromptError> {
+        let messages = match (
+            values.get_messages(&self.variable_name),
+            values.get(&self.variable_name),
+        ) {
+            (Some(messages), _) => messages.to_vec(),
+            (None, Some(text)) => vec![MessageEnum::Human(HumanMessage::new(text))],
+            (None, None) if self.optional => Vec::new(),
+            (None, None) => {
+                return Err(PromptError::MissingVariables(vec![self
+                    .variable_name
+                    .clone()]))
+            }
+        };
+
+        let skip = match self.n_messages {
+            Some(n_messages) => messages.len().saturating_sub(n_messages),
+            None => 0,
+        };
+        Ok(messages.into_iter().skip(skip).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessagePromptTemplate {
+    System(PromptTemplate),
+    Human(PromptTemplate),
+    Ai(PromptTemplate),
+    Chat {
+        role: String,
+        template: PromptTemplate,
+    },
+    Placeholder(MessagesPlaceholder),
+}
+
+impl MessagePromptTemplate {
+    /// Builds a template for `role`. The usual role names ("system", "human"
+    /// or "user", "ai" or "assistant") map to their message types, and
+    /// `("placeholder", "{name}")` is an optional `MessagesPlaceholder`; any
+    /// other role renders as a `ChatMessage`.
+    pub fn new(role: &str, template: impl Into<String>) -> Result<Self, PromptError> {
+        let template = PromptTemplate::new(template)?;
+        Ok(match role {
+            "placeholder" => {
+                let name = template
+                    .variables()
+                    .next()
+                    .filter(|name| template.template() == format!("{{{}}}", name))
+                    .ok_or_else(|| PromptError::InvalidTemplate {
+                        template: template.template().to_string(),
+                        reason: "a placeholder must be a single {variable}".to_string(),
+                    })?;
+                MessagePromptTemplate::Placeholder(MessagesPlaceholder::new_optional(name))
+            }
+            "system" => MessagePromptTemplate::System(template),
+            "human" | "user" => MessagePromptTemplate::Human(template),
+            "ai" | "assistant" => MessagePromptTemplate::Ai(template),
+            role => MessagePromptTemplate::Chat {
+                role: role.to_string(),
+                template,
+            },
+        })
+    }
+
+    pub fn system(template: impl Into<String>) -> Result<Self, PromptError> {
+        Ok(MessagePromptTemplate::System(PromptTemplate::new(
+            template,
+        )?))
+    }
+
+    pub fn human(template: impl Into<String>) -> Result<Self, PromptError> {
+        Ok(MessagePromptTemplate::Human(PromptTemplate::new(template)?))
+    }
+
+    pub fn ai(template: impl Into<String>) -> Result<Self, PromptError> {
+        Ok(MessagePromptTemplate::Ai(PromptTemplate::new(template)?))
+    }
+
+    pub fn placeholder(placeholder: MessagesPlaceholder) -> Self {
+        MessagePromptTemplate::Placeholder(placeholder)
+    }
+
+    pub fn input_variables(&self) -> Vec<String> {
+        self.variables()
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn variables(&self) -> Vec<&str> {
+        match self {
+            MessagePromptTemplate::System(template)
+            | MessagePromptTemplate::Human(template)
+            | MessagePromptTemplate::Ai(template)
+            | MessagePromptTemplate::Chat { template, .. } => template.variables().collect(),
+            MessagePromptTemplate::Placeholder(placeholder) => vec![placeholder.variable_name()],
+        }
+    }
+
+    fn is_optional(&self) -> bool {
+        matches!(self, MessagePromptTemplate::Placeholder(placeholder) if placeholder.is_optional())
+    }
+
+    fn render(&self, values: &PromptValues) -> Result<Vec<MessageEnum>, PromptError> {
+        let message = match self {
+            MessagePromptTemplate::System(template) => {
+                MessageEnum::System(SystemMessage::new(template.render(values)?))
+            }
+            MessagePromptTemplate::Human(template) => {
+                MessageEnum::Human(HumanMessage::new(template.render(values)?))
+            }
+            MessagePromptTemplate::Ai(template) => {
+                MessageEnum::Ai(AiMessage::new(template.render(values)?))
+            }
+            MessagePromptTemplate::Chat { role, template } => {
+                MessageEnum::Chat(ChatMessage::new(template.render(values)?, role.clone()))
+            }
+            MessagePromptTemplate::Placeholder(placeholder) => return placeholder.render(values),
+        };
+        Ok(vec![message])
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChatPromptTemplate {
+    messages: Vec<MessagePromptTemplate>,
+    partial_variables: PromptValues,
+}
+
+impl ChatPromptTemplate {
+    pub fn new(messages: Vec<MessagePromptTemplate>) -> Self {
+        ChatPromptTemplate {
+            messages,
+            partial_variables: PromptValues::new(),
+        }
+    }
+
+    /// Builds a template from `(role, template)` pairs, e.g.
+    /// `[("system", "You are {persona}."), ("human", "{question}")]`.
+    pub fn from_messages<R, T>(
+        messages: impl IntoIterator<Item = (R, T)>,
+    ) -> Result<Self, PromptError>
+    where
+        R: AsRef<str>,
+        T: Into<String>,
+    {
+        messages
+            .into_iter()
+            .map(|(role, template)| MessagePromptTemplate::new(role.as_ref(), template))
+            .collect::<Result<Vec<_>, _>>()
+            .map(ChatPromptTemplate::new)
+    }
+
+    pub fn messages(&self) -> &[MessagePromptTemplate] {
+        &self.messages
+    }
+
+    pub fn push(&mut self, message: MessagePromptTemplate) {
+        self.messages.push(message);
+    }
+
+    pub fn partial_variables(&self) -> &PromptValues {
+        &self.partial_variables
+    }
+
+    /// Fixes the value of a variable so it no longer has to be supplied when
+    /// formatting. Values passed to `format_messages` still take precedence.
+    pub fn set_partial_variable(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.partial_variables.insert(name, value);
+    }
+
+    /// Variables that must be supplied when formatting, i.e. those used by the
+    /// templates and required placeholders that have no partial value.
+    pub fn input_variables(&self) -> Vec<String> {
+        self.required_variables()
+            .into_iter()
+            .filter(|name| !self.partial_variables.contains(name))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Variables of optional placeholders, which may be left out.
+    pub fn optional_variables(&self) -> Vec<String> {
+        let required = self.required_variables();
+        self.variables()
+            .into_iter()
+            .filter(|name| !required.contains(name))
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn format_messages(&self, values: &PromptValues) -> Result<Vec<MessageEnum>, PromptError> {
+        let variables = self.variables();
+        let unexpected: Vec<String> = values
+            .names()
+            .filter(|name| !variables.contains(name))
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if !unexpected.is_empty() {
+            return Err(PromptError::UnexpectedVariables(unexpected));
+        }
+
+        let mut merged = self.partial_variables.clone();
+        merged.extend(values);
+
+        let missing: Vec<String> = self
+            .required_variables()
+            .into_iter()
+            .filter(|name| !merged.contains(name))
+            .map(str::to_string)
+            .collect();
+        if !missing.is_empty() {
+            return Err(PromptError::MissingVariables(missing));
+        }
+
+        let mut messages = Vec::new();
+        for message in &self.messages {
+            messages.extend(message.render(&merged)?);
+        }
+        Ok(messages)
+    }
+
+    fn variables(&self) -> BTreeSet<&str> {
+        self.messages
+            .iter()
+            .flat_map(MessagePromptTemplate::variables)
+            .collect()
+    }
+
+    fn required_variables(&self) -> BTreeSet<&str> {
+        self.messages
+            .iter()
+            .filter(|message| !message.is_optional())
+            .flat_map(MessagePromptTemplate::variables)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BaseMessage;
+
+    fn prompt() -> ChatPromptTemplate {
+        ChatPromptTemplate::from_messages([
+            ("system", "You are a {persona}. Answer in {language}."),
+            ("human", "{question}"),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_format_messages() {
+        let values: PromptValues = [
+            ("persona", "pirate"),
+            ("language", "English"),
+            ("question", "Where is the treasure?"),
+        ]
+        .into_iter()
+        .collect();
+
+        let messages = prompt().format_messages(&values).unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                MessageEnum::System(SystemMessage::new("You are a pirate. Answer in English.")),
+                MessageEnum::Human(HumanMessage::new("Where is the treasure?")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_variables_exclude_partials() {
+        let mut prompt = prompt();
+        assert_eq!(
+            prompt.input_variables(),
+            vec!["language", "persona", "question"]
+        );
+
+        prompt.set_partial_variable("language", "French");
+        assert_eq!(prompt.input_variables(), vec!["persona", "question"]);
+
+        let values: PromptValues = [("persona", "chef"), ("question", "Dinner?")]
+            .into_iter()
+            .collect();
+        let messages = prompt.format_messages(&values).unwrap();
+        assert_eq!(messages[0].content(), "You are a chef. Answer in French.");
+    }
+
+    #[test]
+    fn test_values_override_partials() {
+        let mut prompt = prompt();
+        prompt.set_partial_variable("language", "French");
+
+        let values: PromptValues = [
+            ("persona", "chef"),
+            ("language", "Italian"),
+            ("question", "Dinner?"),
+        ]
+        .into_iter()
+        .collect();
+        let messages = prompt.format_messages(&values).unwrap();
+        assert_eq!(messages[0].content(), "You are a chef. Answer in Italian.");
+    }
+
+    #[test]
+    fn test_missing_variables() {
+        let values: PromptValues = [("question", "Dinner?")].into_iter().collect();
+
+        assert_eq!(
+            prompt().format_messages(&values).unwrap_err(),
+            PromptError::MissingVariables(vec!["language".to_string(), "persona".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_unexpected_variables() {
+        let values: PromptValues = [
+            ("persona", "chef"),
+            ("language", "English"),
+            ("question", "Dinner?"),
+            ("mood", "happy"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            prompt().format_messages(&values).unwrap_err(),
+            PromptError::UnexpectedVariables(vec!["mood".to_string()])
+        );
+    }
+
+    fn history() -> Vec<MessageEnum> {
+        vec![
+            MessageEnum::Human(HumanMessage::new("Hi, I'm Ada.")),
+            MessageEnum::Ai(AiMessage::new("Hello Ada!")),
+            MessageEnum::Human(HumanMessage::new("What's 2+2?")),
+            MessageEnum::Ai(AiMessage::new("4")),
+        ]
+    }
+
+    fn prompt_with_history(placeholder: MessagesPlaceholder) -> ChatPromptTemplate {
+        ChatPromptTemplate::new(vec![
+            MessagePromptTemplate::system("You are a helpful assistant.").unwrap(),
+            MessagePromptTemplate::placeholder(placeholder),
+            MessagePromptTemplate::human("{question}").unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_placeholder_splices_history() {
+        let prompt = prompt_with_history(MessagesPlaceholder::new("history"));
+        assert_eq!(prompt.input_variables(), vec!["history", "question"]);
+
+        let mut values = PromptValues::new();
+        values.insert("question", "What's my name?");
+        values.insert_messages("history", history());
+
+        let messages = prompt.format_messages(&values).unwrap();
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[1..5], history()[..]);
+        assert_eq!(messages[5].content(), "What's my name?");
+    }
+
+    #[test]
+    fn test_required_placeholder_is_missing() {
+        let prompt = prompt_with_history(MessagesPlaceholder::new("history"));
+        let values: PromptValues = [("question", "Hi")].into_iter().collect();
+
+        assert_eq!(
+            prompt.format_messages(&values).unwrap_err(),
+            PromptError::MissingVariables(vec!["history".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_optional_placeholder() {
+        let prompt = ChatPromptTemplate::from_messages([
+            ("system", "You are a helpful assistant."),
+            ("placeholder", "{history}"),
+            ("human", "{question}"),
+        ])
+        .unwrap();
+        assert_eq!(prompt.input_variables(), vec!["question"]);
+        assert_eq!(prompt.optional_variables(), vec!["history"]);
+
+        let values: PromptValues = [("question", "Hi")].into_iter().collect();
+        let messages = prompt.format_messages(&values).unwrap();
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn test_placeholder_keeps_last_n_messages() {
+        let mut placeholder = MessagesPlaceholder::new("history");
+        placeholder.set_n_messages(Some(2));
+        let prompt = prompt_with_history(placeholder);
+
+        let mut values = PromptValues::new();
+        values.insert("question", "And 3+3?");
+        values.insert_messages("history", history());
+
+        let messages = prompt.format_messages(&values).unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[1..3], history()[2..]);
+    }
+
+    #[test]
+    fn test_messages_for_text_variable_are_rejected() {
+        let prompt = prompt_with_history(MessagesPlaceholder::new_optional("history"));
+
+        let mut values = PromptValues::new();
+        values.insert_messages("question", history());
+
+        assert!(matches!(
+            prompt.format_messages(&values),
+            Err(PromptError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_placeholder_template() {
+        let result = ChatPromptTemplate::from_messages([("placeholder", "History: {history}")]);
+        assert!(matches!(result, Err(PromptError::InvalidTemplate { .. })));
+    }
+
+    #[test]
+    fn test_custom_role_renders_chat_message() {
+        let prompt = ChatPromptTemplate::from_messages([("critic", "Review: {draft}")]).unwrap();
+        let values: PromptValues = [("draft", "Roses are red")].into_iter().collect();
+
+        let messages = prompt.format_messages(&values).unwrap();
+        let chat_message = messages[0].as_chat().unwrap();
+        assert_eq!(chat_message.role(), "critic");
+        assert_eq!(chat_message.content(), "Review: Roses are red");
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use crate::{AiMessage, BaseMessage, HumanMessage, MessageEnum};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub output: String,
+}
+
+impl Example {
+    pub fn new(input: impl Into<String>, output: impl Into<String>) -> Self {
+        Example {
+            input: input.into(),
+            output: output.into(),
+        }
+    }
+
+    /// Renders the example as a human/AI turn pair with `example` set.
+    pub fn to_messages(&self) -> [MessageEnum; 2] {
+        [
+            MessageEnum::Human(HumanMessage::new_with_example(self.input.as_str(), true)),
+            MessageEnum::Ai(AiMessage::new_with_example(self.output.as_str(), true)),
+        ]
+    }
+}
+
+/// Chooses which examples to show for a given input.
+pub trait ExampleSelector {
+    fn select_examples<'a>(&self, examples: &'a [Example], input: &str) -> Vec<&'a Example>;
+}
+
+/// Keeps examples, in order, until their combined length plus the length of
+/// the input would exceed `max_length`. Length is measured in words unless a
+/// different function is set.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthBasedExampleSelector {
+    max_length: usize,
+    length_fn: fn(&str) -> usize,
+}
+
+impl LengthBasedExampleSelector {
+    pub fn new(max_length: usize) -> Self {
+        LengthBasedExampleSelector {
+            max_length,
+            length_fn: word_count,
+        }
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    pub fn set_length_fn(&mut self, length_fn: fn(&str) -> usize) {
+        self.length_fn = length_fn;
+    }
+}
+
+impl ExampleSelector for LengthBasedExampleSelector {
+    fn select_examples<'a>(&self, examples: &'a [Example], input: &str) -> Vec<&'a Example> {
+        let mut remaining = self.max_length.saturating_sub((self.length_fn)(input));
+        let mut selected = Vec::new();
+
+        for example in examples {
+            let length = (self.length_fn)(&example.input) + (self.length_fn)(&example.output);
+            if length > remaining {
+                break;
+            }
+            remaining -= length;
+            selected.push(example);
+        }
+        selected
+    }
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Renders few-shot examples as human/AI message pairs marked as examples,
+/// ready to be spliced into a prompt through a `MessagesPlaceholder`.
+#[derive(Default)]
+pub struct FewShotChatPrompt {
+    examples: Vec<Example>,
+    selector: Option<Box<dyn ExampleSelector + Send + Sync>>,
+}
+
+impl FewShotChatPrompt {
+    pub fn new(examples: Vec<Example>) -> Self {
+        FewShotChatPrompt {
+            examples,
+            selector: None,
+        }
+    }
+
+    pub fn examples(&self) -> &[Example] {
+        &self.examples
+    }
+
+    pub fn add_example(&mut self, example: Example) {
+        self.examples.push(example);
+    }
+
+    /// Uses `selector` to pick the examples for each input. Without a
+    /// selector every example is rendered.
+    pub fn set_selector(&mut self, selector: impl ExampleSelector + Send + Sync + 'static) {
+        self.selector = Some(Box::new(selector));
+    }
+
+    pub fn select_examples(&self, input: &str) -> Vec<&Example> {
+        match &self.selector {
+            Some(selector) => selector.select_examples(&self.examples, input),
+            None => self.examples.iter().collect(),
+        }
+    }
+
+    pub fn format_messages(&self, input: &str) -> Vec<MessageEnum> {
+        self.select_examples(input)
+            .into_iter()
+            .flat_map(Example::to_messages)
+            .collect()
+    }
+}
+
+impl fmt::Debug for FewShotChatPrompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FewShotChatPrompt")
+            .field("examples", &self.examples)
+            .field("has_selector", &self.selector.is_some())
+            .finish()
+    }
+}
+
+/// Removes every message flagged as an example, e.g. before logging a
+/// conversation.
+pub fn strip_examples(mut messages: Vec<MessageEnum>) -> Vec<MessageEnum> {
+    messages.retain(|message| !message.is_example());
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::{ChatPromptTemplate, PromptValues};
+
+    fn examples() -> Vec<Example> {
+        vec![
+            Example::new("2 + 2", "4"),
+            Example::new("2 + 3", "5"),
+            Example::new("What is the square root of sixteen?", "4"),
+        ]
+    }
+
+    #[test]
+    fn test_format_messages_marks_examples() {
+        let few_shot = FewShotChatPrompt::new(examples());
+
+        let messages = few_shot.format_messages("3 + 3");
+        assert_eq!(messages.len(), 6);
+        assert!(messages.iter().all(|message| message.is_example()));
+        assert!(matches!(messages[0], MessageEnum::Human(_)));
+        assert!(matches!(messages[1], MessageEnum::Ai(_)));
+        assert_eq!(messages[3].content(), "5");
+    }
+
+    #[test]
+    fn test_length_based_selector() {
+        let mut few_shot = FewShotChatPrompt::new(examples());
+        few_shot.set_selector(LengthBasedExampleSelector::new(10));
+
+        // "3 + 3" takes 3 words, leaving 7: the first two examples use 4 words
+        // each, so only the first fits.
+        let selected = few_shot.select_examples("3 + 3");
+        assert_eq!(selected, vec![&examples()[0]]);
+
+        let selected = few_shot.select_examples("");
+        assert_eq!(selected, vec![&examples()[0], &examples()[1]]);
+    }
+
+    #[test]
+    fn test_custom_selector() {
+        struct Matching;
+
+        impl ExampleSelector for Matching {
+            fn select_examples<'a>(
+                &self,
+                examples: &'a [Example],
+                input: &str,
+            ) -> Vec<&'a Example> {
+                examples
+                    .iter()
+                    .filter(|example| example.input.contains(input))
+                    .collect()
+            }
+        }
+
+        let mut few_shot = FewShotChatPrompt::new(examples());
+        few_shot.set_selector(Matching);
+
+        let messages = few_shot.format_messages("square root");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content(), "What is the square root of sixteen?");
+    }
+
+    #[test]
+    fn test_strip_examples_from_prompt() {
+        let prompt = ChatPromptTemplate::from_messages([
+            ("system", "You are a calculator."),
+            ("placeholder", "{examples}"),
+            ("human", "{question}"),
+        ])
+        .unwrap();
+        let few_shot = FewShotChatPrompt::new(examples());
+
+        let mut values = PromptValues::new();
+        values.insert("question", "3 + 3");
+        values.insert_messages("examples", few_shot.format_messages("3 + 3"));
+
+        let messages = prompt.format_messages(&values).unwrap();
+        assert_eq!(messages.len(), 8);
+
+        let stripped = strip_examples(messages);
+        assert_eq!(stripped.len(), 2);
+        assert_eq!(stripped[1].content(), "3 + 3");
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::MessageEnum;
+
+pub mod chat;
+pub mod few_shot;
+pub mod template;
+
+pub use chat::{ChatPromptTemplate, MessagePromptTemplate, MessagesPlaceholder};
+pub use few_shot::{
+    strip_examples, Example, ExampleSelector, FewShotChatPrompt, LengthBasedExampleSelector,
+};
+pub use template::PromptTemplate;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptError {
+    InvalidTemplate { template: String, reason: String },
+    MissingVariables(Vec<String>),
+    UnexpectedVariables(Vec<String>),
+    InvalidValue { name: String, reason: String },
+}
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PromptError::InvalidTemplate { template, reason } => {
+                write!(f, "Invalid template {:?}: {}", template, reason)
+            }
+            PromptError::MissingVariables(names) => {
+                write!(f, "Missing variables: {}", names.join(", "))
+            }
+            PromptError::UnexpectedVariables(names) => {
+                write!(f, "Unexpected variables: {}", names.join(", "))
+            }
+            PromptError::InvalidValue { name, reason } => {
+                write!(f, "Invalid value for variable {}: {}", name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PromptError {}
+
+/// Values supplied when rendering a template, keyed by variable name. A
+/// variable holds either text or, for a `MessagesPlaceholder`, a list of
+/// messages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptValues {
+    text: HashMap<String, String>,
+    messages: HashMap<String, Vec<MessageEnum>>,
+}
+
+impl PromptValues {
+    pub fn new() -> Self {
+        PromptValues::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        self.messages.remove(&name);
+        self.text.insert(name, value.into());
+    }
+
+    pub fn insert_messages(&mut self, name: impl Into<String>, messages: Vec<MessageEnum>) {
+        let name = name.into();
+        self.text.remove(&name);
+        self.messages.insert(name, messages);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.text.get(name).map(String::as_str)
+    }
+
+    pub fn get_messages(&self, name: &str) -> Option<&[MessageEnum]> {
+        self.messages.get(name).map(Vec::as_slice)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.text.contains_key(name) || self.messages.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.text
+            .keys()
+            .chain(self.messages.keys())
+            .map(String::as_str)
+    }
+
+    pub(crate) fn extend(&mut self, other: &PromptValues) {
+        for (name, value) in &other.text {
+            self.insert(name.clone(), value.clone());
+        }
+        for (name, messages) in &other.messages {
+            self.insert_messages(name.clone(), messages.clone());
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for PromptValues {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut values = PromptValues::new();
+        for (name, value) in iter {
+            values.insert(name, value);
+        }
+        values
+    }
+}
+
+impl From<HashMap<String, String>> for PromptValues {
+    fn from(text: HashMap<String, String>) -> Self {
+        PromptValues {
+            text,
+            messages: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_values_from_iter() {
+        let values: PromptValues = [("name", "Ada"), ("topic", "engines")]
+            .into_iter()
+            .collect();
+
+        assert_eq!(values.get("name"), Some("Ada"));
+        assert!(values.contains("topic"));
+        assert!(!values.contains("missing"));
+    }
+
+    #[test]
+    fn test_prompt_values_text_and_messages_share_names() {
+        let mut values = PromptValues::new();
+        values.insert("history", "plain text");
+        values.insert_messages("history", vec![]);
+
+        assert_eq!(values.get("history"), None);
+        assert_eq!(values.get_messages("history"), Some(&[][..]));
+        assert_eq!(values.names().collect::<Vec<_>>(), vec!["history"]);
+    }
+
+    #[test]
+    fn test_prompt_error_display() {
+        let err = PromptError::MissingVariables(vec!["name".to_string(), "topic".to_string()]);
+        assert_eq!(err.to_string(), "Missing variables: name, topic");
+    }
+}
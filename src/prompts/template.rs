@@ -0,0 +1,232 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::{PromptError, PromptValues};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Variable(String),
+}
+
+/// A string template using f-string style `{name}` placeholders. Literal
+/// braces are written as `{{` and `}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    template: String,
+    segments: Vec<Segment>,
+}
+
+impl PromptTemplate {
+    pub fn new(template: impl Into<String>) -> Result<Self, PromptError> {
+        let template = template.into();
+        let segments = parse(&template)?;
+        Ok(PromptTemplate { template, segments })
+    }
+
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Names of the variables referenced by the template, sorted and without
+    /// duplicates.
+    pub fn input_variables(&self) -> Vec<String> {
+        self.variables()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn format(&self, values: &PromptValues) -> Result<String, PromptError> {
+        let names: BTreeSet<&str> = self.variables().collect();
+        let unexpected: Vec<String> = values
+            .names()
+            .filter(|name| !names.contains(name))
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if !unexpected.is_empty() {
+            return Err(PromptError::UnexpectedVariables(unexpected));
+        }
+        self.render(values)
+    }
+
+    pub(crate) fn variables(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Variable(name) => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Renders the template, ignoring values the template does not use.
+    pub(crate) fn render(&self, values: &PromptValues) -> Result<String, PromptError> {
+        if let Some(name) = self
+            .variables()
+            .find(|name| values.get_messages(name).is_some())
+        {
+            return Err(PromptError::InvalidValue {
+                name: name.to_string(),
+                reason: "expected text but got a list of messages".to_string(),
+            });
+        }
+
+        let missing: Vec<String> = self
+            .variables()
+            .filter(|name| values.get(name).is_none())
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if !missing.is_empty() {
+            return Err(PromptError::MissingVariables(missing));
+        }
+
+        let mut output = String::with_capacity(self.template.len());
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => output.push_str(text),
+                Segment::Variable(name) => output.push_str(values.get(name).unwrap_or_default()),
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl fmt::Display for PromptTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.template)
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Segment>, PromptError> {
+    let invalid = |reason: String| PromptError::InvalidTemplate {
+        template: template.to_string(),
+        reason,
+    };
+
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.char_indices().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '{' if chars.peek().map(|&(_, next)| next) == Some('{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek().map(|&(_, next)| next) == Some('}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, c)) => name.push(c),
+                        None => return Err(invalid(format!("unclosed '{{' at byte {}", position))),
+                    }
+                }
+                if !is_identifier(&name) {
+                    return Err(invalid(format!("invalid variable name {:?}", name)));
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Variable(name));
+            }
+            '}' => return Err(invalid(format!("single '}}' at byte {}", position))),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let template = PromptTemplate::new("Tell me a {adjective} joke about {topic}.").unwrap();
+        let values: PromptValues = [("adjective", "short"), ("topic", "Rust")]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            template.format(&values).unwrap(),
+            "Tell me a short joke about Rust."
+        );
+        assert_eq!(template.input_variables(), vec!["adjective", "topic"]);
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let template = PromptTemplate::new("Return {{\"name\": \"{name}\"}}").unwrap();
+        let values: PromptValues = [("name", "Ada")].into_iter().collect();
+
+        assert_eq!(
+            template.format(&values).unwrap(),
+            "Return {\"name\": \"Ada\"}"
+        );
+        assert_eq!(template.input_variables(), vec!["name"]);
+    }
+
+    #[test]
+    fn test_repeated_variable() {
+        let template = PromptTemplate::new("{word}, {word}, {word}!").unwrap();
+        let values: PromptValues = [("word", "hey")].into_iter().collect();
+
+        assert_eq!(template.format(&values).unwrap(), "hey, hey, hey!");
+        assert_eq!(template.input_variables(), vec!["word"]);
+    }
+
+    #[test]
+    fn test_missing_and_unexpected_variables() {
+        let template = PromptTemplate::new("Hello {name}").unwrap();
+
+        assert_eq!(
+            template.format(&PromptValues::new()).unwrap_err(),
+            PromptError::MissingVariables(vec!["name".to_string()])
+        );
+
+        let values: PromptValues = [("name", "Ada"), ("age", "36")].into_iter().collect();
+        assert_eq!(
+            template.format(&values).unwrap_err(),
+            PromptError::UnexpectedVariables(vec!["age".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        for template in [
+            "Hello {name",
+            "Hello name}",
+            "Hello {}",
+            "Hello {first name}",
+        ] {
+            assert!(
+                matches!(
+                    PromptTemplate::new(template),
+                    Err(PromptError::InvalidTemplate { .. })
+                ),
+                "{} should be rejected",
+                template
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+
+define_message!(Remove);
+
+impl RemoveMessage {
+    /// Builds a marker that deletes the message with the given id when passed
+    /// through `add_messages`.
+    pub fn with_id(id: impl Into<String>) -> Self {
+        let mut message = RemoveMessage::new("");
+        message.set_id(Some(id.into()));
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_remove_message_with_id() {
+        let remove_message = RemoveMessage::with_id("msg_1");
+        assert_eq!(remove_message.id(), Some("msg_1"));
+        assert_eq!(remove_message.content(), "");
+        assert_eq!(remove_message.message_type(), &MessageType::Remove);
+    }
+
+    #[test]
+    fn test_remove_message_serialization() {
+        let remove_message = RemoveMessage::with_id("msg_1");
+
+        let serialized = serde_json::to_value(&remove_message).unwrap();
+        assert_eq!(
+            serialized,
+            json!({
+                "content": "",
+                "example": false,
+                "message_type": "Remove",
+                "id": "msg_1"
+            })
+        );
+
+        let deserialized: RemoveMessage = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, remove_message);
+    }
+}
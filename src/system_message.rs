@@ -46,11 +46,11 @@ mod tests {
         system_message
             .base
             .additional_kwargs
-            .insert("key".to_string(), "value".to_string());
+            .insert("key".to_string(), "value".into());
 
         assert_eq!(
             system_message.additional_kwargs().get("key"),
-            Some(&"value".to_string())
+            Some(&"value".into())
         );
     }
 
@@ -60,11 +60,11 @@ mod tests {
         system_message
             .base
             .response_metadata
-            .insert("source".to_string(), "System Process".to_string());
+            .insert("source".to_string(), "System Process".into());
 
         assert_eq!(
             system_message.response_metadata().get("source"),
-            Some(&"System Process".to_string())
+            Some(&"System Process".into())
         );
     }
 
@@ -110,21 +110,21 @@ mod tests {
         system_message
             .base
             .additional_kwargs
-            .insert("task".to_string(), "system monitoring".to_string());
+            .insert("task".to_string(), "system monitoring".into());
         system_message
             .base
             .response_metadata
-            .insert("process".to_string(), "systemd".to_string());
+            .insert("process".to_string(), "systemd".into());
 
         assert_eq!(system_message.id(), Some("SYS123"));
         assert_eq!(system_message.name(), Some("System Bot"));
         assert_eq!(
             system_message.additional_kwargs().get("task"),
-            Some(&"system monitoring".to_string())
+            Some(&"system monitoring".into())
         );
         assert_eq!(
             system_message.response_metadata().get("process"),
-            Some(&"systemd".to_string())
+            Some(&"systemd".into())
         );
 
         let expected_json = json!({
@@ -0,0 +1,104 @@
+use crate::MessageEnum;
+
+use super::{count_with_overhead, MessageOverhead, TokenCounter};
+
+/// Estimates tokens from character counts. English text averages about four
+/// characters per token with common BPE vocabularies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CharRatioCounter {
+    chars_per_token: f64,
+    overhead: MessageOverhead,
+}
+
+impl CharRatioCounter {
+    /// # Panics
+    ///
+    /// Panics if `chars_per_token` is not positive.
+    pub fn new(chars_per_token: f64) -> Self {
+        assert!(
+            chars_per_token > 0.0,
+            "chars_per_token must be positive, got {}",
+            chars_per_token
+        );
+        CharRatioCounter {
+            chars_per_token,
+            overhead: MessageOverhead::default(),
+        }
+    }
+
+    pub fn chars_per_token(&self) -> f64 {
+        self.chars_per_token
+    }
+
+    pub fn overhead(&self) -> &MessageOverhead {
+        &self.overhead
+    }
+
+    pub fn set_overhead(&mut self, overhead: MessageOverhead) {
+        self.overhead = overhead;
+    }
+
+    pub fn count_text(&self, text: &str) -> usize {
+        (text.chars().count() as f64 / self.chars_per_token).ceil() as usize
+    }
+}
+
+impl Default for CharRatioCounter {
+    fn default() -> Self {
+        CharRatioCounter::new(4.0)
+    }
+}
+
+impl TokenCounter for CharRatioCounter {
+    fn count_message(&self, message: &MessageEnum) -> usize {
+        count_with_overhead(message, &self.overhead, |text| self.count_text(text))
+    }
+
+    fn count_messages(&self, messages: &[MessageEnum]) -> usize {
+        self.overhead.per_conversation
+            + messages
+                .iter()
+                .map(|message| self.count_message(message))
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HumanMessage, SystemMessage};
+
+    #[test]
+    fn test_count_text_rounds_up() {
+        let counter = CharRatioCounter::default();
+        assert_eq!(counter.count_text(""), 0);
+        assert_eq!(counter.count_text("abcd"), 1);
+        assert_eq!(counter.count_text("abcde"), 2);
+        // Counts characters, not bytes.
+        assert_eq!(counter.count_text("héllo wörld"), 3);
+    }
+
+    #[test]
+    fn test_count_messages_with_overhead() {
+        let counter = CharRatioCounter::default();
+        let messages = vec![
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+            MessageEnum::Human(HumanMessage::new("Hello there")),
+        ];
+
+        // system: 3 + "system" 2 + "Be brief." 3; human: 3 + "human" 2 + 3
+        assert_eq!(counter.count_message(&messages[0]), 8);
+        assert_eq!(counter.count_messages(&messages), 3 + 8 + 8);
+        assert_eq!(counter.count_messages(&[]), 3);
+
+        let mut counter = CharRatioCounter::new(2.0);
+        counter.set_overhead(MessageOverhead::NONE);
+        assert_eq!(counter.count_messages(&messages), 3 + 5 + 3 + 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "chars_per_token must be positive")]
+    fn test_rejects_zero_ratio() {
+        CharRatioCounter::new(0.0);
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::ops::Range;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::MessageEnum;
+
+use super::{count_with_overhead, MessageOverhead, TokenCounter};
+
+#[derive(Debug)]
+pub enum TokenizerError {
+    Io(io::Error),
+    InvalidLine { line: usize, reason: String },
+}
+
+impl fmt::Display for TokenizerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenizerError::Io(err) => write!(f, "Failed to read vocabulary: {}", err),
+            TokenizerError::InvalidLine { line, reason } => {
+                write!(f, "Invalid vocabulary line {}: {}", line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TokenizerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TokenizerError::Io(err) => Some(err),
+            TokenizerError::InvalidLine { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for TokenizerError {
+    fn from(err: io::Error) -> Self {
+        TokenizerError::Io(err)
+    }
+}
+
+/// Counts tokens with byte-pair encoding over a tiktoken vocabulary, such as
+/// `cl100k_base.tiktoken` or `o200k_base.tiktoken`. Each line of the file
+/// holds a base64 encoded token and its rank.
+///
+/// Text is split with the `cl100k_base` pre-tokenization rules before
+/// merging. Special tokens are not recognised and are counted as plain text.
+#[derive(Debug, Clone)]
+pub struct BpeCounter {
+    ranks: HashMap<Vec<u8>, u32>,
+    overhead: MessageOverhead,
+}
+
+impl BpeCounter {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TokenizerError> {
+        BpeCounter::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, TokenizerError> {
+        let mut ranks = HashMap::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |reason: String| TokenizerError::InvalidLine {
+                line: index + 1,
+                reason,
+            };
+
+            let (token, rank) = line
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| invalid("expected a token and a rank".to_string()))?;
+            let token = STANDARD
+                .decode(token)
+                .map_err(|err| invalid(format!("invalid base64 token: {}", err)))?;
+            let rank = rank
+                .parse()
+                .map_err(|err| invalid(format!("invalid rank: {}", err)))?;
+            ranks.insert(token, rank);
+        }
+
+        Ok(BpeCounter {
+            ranks,
+            overhead: MessageOverhead::default(),
+        })
+    }
+
+    pub fn vocabulary_size(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn overhead(&self) -> &MessageOverhead {
+        &self.overhead
+    }
+
+    pub fn set_overhead(&mut self, overhead: MessageOverhead) {
+        self.overhead = overhead;
+    }
+
+    pub fn count_text(&self, text: &str) -> usize {
+        pre_tokenize(text)
+            .into_iter()
+            .map(|piece| self.count_piece(piece.as_bytes()))
+            .sum()
+    }
+
+    /// Applies the lowest ranked merge until none is left. Bytes missing from
+    /// the vocabulary count as one token each.
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if self.ranks.contains_key(piece) {
+            return 1;
+        }
+
+        let mut parts: Vec<Range<usize>> = (0..piece.len()).map(|i| i..i + 1).collect();
+        loop {
+            let best = parts
+                .windows(2)
+                .enumerate()
+                .filter_map(|(i, pair)| {
+                    let rank = self.ranks.get(&piece[pair[0].start..pair[1].end])?;
+                    Some((*rank, i))
+                })
+                .min();
+
+            match best {
+                Some((_, i)) => {
+                    parts[i].end = parts[i + 1].end;
+                    parts.remove(i + 1);
+                }
+                None => return parts.len(),
+            }
+        }
+    }
+}
+
+impl TokenCounter for BpeCounter {
+    fn count_message(&self, message: &MessageEnum) -> usize {
+        count_with_overhead(message, &self.overhead, |text| self.count_text(text))
+    }
+
+    fn count_messages(&self, messages: &[MessageEnum]) -> usize {
+        self.overhead.per_conversation
+            + messages
+                .iter()
+                .map(|message| self.count_message(message))
+                .sum::<usize>()
+    }
+}
+
+/// Splits text the way the `cl100k_base` pattern does:
+///
+/// ```text
+/// (?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}
+/// | ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+
+/// ```
+fn pre_tokenize(text: &str) -> Vec<&str> {
+    let offsets: Vec<usize> = text.char_indices().map(|(offset, _)| offset).collect();
+    let chars: Vec<char> = text.chars().collect();
+    let mut pieces = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let end = start + piece_len(&chars[start..]);
+        let byte_end = offsets.get(end).copied().unwrap_or(text.len());
+        pieces.push(&text[offsets[start]..byte_end]);
+        start = end;
+    }
+    pieces
+}
+
+fn piece_len(chars: &[char]) -> usize {
+    let first = chars[0];
+    let at = |i: usize| chars.get(i).copied();
+
+    if first == '\'' {
+        let next: String = chars[1..]
+            .iter()
+            .take(2)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        if next.starts_with(['s', 't', 'm', 'd']) {
+            return 2;
+        }
+        if ["re", "ve", "ll"].contains(&next.as_str()) {
+            return 3;
+        }
+    }
+
+    let lead = usize::from(!is_letter(first) && !is_number(first) && !is_newline(first));
+    if at(lead).is_some_and(is_letter) {
+        return lead + run(&chars[lead..], is_letter);
+    }
+
+    if is_number(first) {
+        return run(chars, is_number).min(3);
+    }
+
+    let lead = usize::from(first == ' ');
+    if at(lead).is_some_and(is_punctuation) {
+        let len = lead + run(&chars[lead..], is_punctuation);
+        return len + run(&chars[len..], is_newline);
+    }
+
+    let whitespace = run(chars, char::is_whitespace);
+    if let Some(newline) = chars[..whitespace].iter().rposition(|&c| is_newline(c)) {
+        newline + 1
+    } else if whitespace == chars.len() || whitespace == 1 {
+        whitespace
+    } else {
+        whitespace - 1
+    }
+}
+
+fn run(chars: &[char], predicate: fn(char) -> bool) -> usize {
+    chars.iter().take_while(|&&c| predicate(c)).count()
+}
+
+fn is_letter(c: char) -> bool {
+    c.is_alphabetic()
+}
+
+fn is_number(c: char) -> bool {
+    c.is_numeric()
+}
+
+fn is_newline(c: char) -> bool {
+    c == '\r' || c == '\n'
+}
+
+fn is_punctuation(c: char) -> bool {
+    !c.is_whitespace() && !is_letter(c) && !is_number(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HumanMessage;
+
+    fn vocabulary(tokens: &[&str]) -> String {
+        let mut lines: Vec<String> = (0..=255u8)
+            .map(|byte| format!("{} {}", STANDARD.encode([byte]), byte))
+            .collect();
+        for (rank, token) in tokens.iter().enumerate() {
+            lines.push(format!("{} {}", STANDARD.encode(token), 256 + rank));
+        }
+        lines.join("\n")
+    }
+
+    fn counter(tokens: &[&str]) -> BpeCounter {
+        BpeCounter::from_reader(vocabulary(tokens).as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_pre_tokenize() {
+        assert_eq!(
+            pre_tokenize("Hello world, it's 12345!\n\n  ok  "),
+            vec!["Hello", " world", ",", " it", "'s", " ", "123", "45", "!\n\n", " ", " ok", "  "]
+        );
+        assert_eq!(pre_tokenize("a\tb  c"), vec!["a", "\tb", " ", " c"]);
+        assert_eq!(pre_tokenize("x \n y"), vec!["x", " \n", " y"]);
+        assert_eq!(pre_tokenize("héllo"), vec!["héllo"]);
+    }
+
+    #[test]
+    fn test_count_text_merges_by_rank() {
+        let counter = counter(&["he", "ll", "hell", "llo", " w", "or", " wor"]);
+
+        // "hello" merges he + ll -> hell, leaving "hell" + "o"; "llo" is never
+        // reached because "ll" was consumed by the lower ranked "hell".
+        assert_eq!(counter.count_piece(b"hello"), 2);
+        // " world" -> " w" + "or" -> " wor" + "l" + "d"
+        assert_eq!(counter.count_piece(b" world"), 3);
+        assert_eq!(counter.count_text("hello world"), 5);
+        assert_eq!(counter.count_text(""), 0);
+        assert_eq!(counter.vocabulary_size(), 263);
+    }
+
+    #[test]
+    fn test_unknown_bytes_count_individually() {
+        let counter = BpeCounter::from_reader("aGk= 0".as_bytes()).unwrap();
+
+        assert_eq!(counter.count_text("hi"), 1);
+        assert_eq!(counter.count_text("hey"), 3);
+    }
+
+    #[test]
+    fn test_count_messages() {
+        let mut counter = counter(&["Hi", "human"]);
+        let messages = vec![MessageEnum::Human(HumanMessage::new("Hi there"))];
+
+        // 3 per message + "human" 1 + "Hi" 1 + " there" 6
+        assert_eq!(counter.count_message(&messages[0]), 11);
+        assert_eq!(counter.count_messages(&messages), 14);
+
+        counter.set_overhead(MessageOverhead::NONE);
+        assert_eq!(counter.count_messages(&messages), 8);
+    }
+
+    #[test]
+    fn test_invalid_vocabulary() {
+        let err = BpeCounter::from_reader("aGk= 0\nnot-a-rank".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid vocabulary line 2: expected a token and a rank"
+        );
+
+        let err = BpeCounter::from_reader("aGk= x".as_bytes()).unwrap_err();
+        assert!(matches!(err, TokenizerError::InvalidLine { line: 1, .. }));
+
+        let err = BpeCounter::from_file("does/not/exist.tiktoken").unwrap_err();
+        assert!(matches!(err, TokenizerError::Io(_)));
+    }
+}
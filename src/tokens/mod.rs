@@ -0,0 +1,145 @@
+use crate::{BaseMessage, MessageEnum};
+
+pub mod approximate;
+pub mod bpe;
+
+pub use approximate::CharRatioCounter;
+pub use bpe::{BpeCounter, TokenizerError};
+
+/// Counts the tokens a message costs when sent to a model.
+pub trait TokenCounter {
+    fn count_message(&self, message: &MessageEnum) -> usize;
+
+    /// Counts a whole conversation. Counters for formats that charge a fixed
+    /// cost per request include it here, so the result for an empty slice is
+    /// that fixed cost.
+    fn count_messages(&self, messages: &[MessageEnum]) -> usize {
+        messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum()
+    }
+}
+
+impl<F> TokenCounter for F
+where
+    F: Fn(&MessageEnum) -> usize,
+{
+    fn count_message(&self, message: &MessageEnum) -> usize {
+        self(message)
+    }
+}
+
+/// Fixed token costs that chat formats add around message text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageOverhead {
+    /// Added once for every message, covering the role and separators.
+    pub per_message: usize,
+    /// Added for every message that carries a `name`.
+    pub per_name: usize,
+    /// Added once per request, e.g. to prime the assistant reply.
+    pub per_conversation: usize,
+}
+
+impl MessageOverhead {
+    pub const NONE: MessageOverhead = MessageOverhead {
+        per_message: 0,
+        per_name: 0,
+        per_conversation: 0,
+    };
+
+    /// The overhead of the OpenAI chat format.
+    pub const OPENAI: MessageOverhead = MessageOverhead {
+        per_message: 3,
+        per_name: 1,
+        per_conversation: 3,
+    };
+}
+
+impl Default for MessageOverhead {
+    fn default() -> Self {
+        MessageOverhead::OPENAI
+    }
+}
+
+/// Counts `message` by applying `count_text` to every text field a chat
+/// format sends, plus the fixed `overhead`.
+pub(crate) fn count_with_overhead<F>(
+    message: &MessageEnum,
+    overhead: &MessageOverhead,
+    count_text: F,
+) -> usize
+where
+    F: Fn(&str) -> usize,
+{
+    let mut tokens =
+        overhead.per_message + count_text(message.role()) + count_text(&message.content());
+
+    if let Some(name) = message.name() {
+        tokens += overhead.per_name + count_text(name);
+    }
+
+    match message {
+        MessageEnum::Ai(ai) => {
+            for tool_call in ai.tool_calls() {
+                tokens += count_text(tool_call.name()) + count_text(&tool_call.args().to_string());
+                tokens += tool_call.id().map_or(0, &count_text);
+            }
+        }
+        MessageEnum::Tool(tool) => tokens += count_text(tool.tool_call_id()),
+        _ => {}
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AiMessage, HumanMessage, ToolCall};
+    use serde_json::json;
+
+    fn words(text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+
+    #[test]
+    fn test_closure_counter() {
+        let counter = |message: &MessageEnum| message.content().len();
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("Hi")),
+            MessageEnum::Ai(AiMessage::new("Hello!")),
+        ];
+
+        assert_eq!(counter.count_message(&messages[0]), 2);
+        assert_eq!(counter.count_messages(&messages), 8);
+        assert_eq!(counter.count_messages(&[]), 0);
+    }
+
+    #[test]
+    fn test_count_with_overhead() {
+        let mut human = HumanMessage::new("What is the weather");
+        human.set_name(Some("ada".to_string()));
+        let human = MessageEnum::Human(human);
+
+        // per_message + role + content + per_name + name
+        assert_eq!(
+            count_with_overhead(&human, &MessageOverhead::OPENAI, words),
+            3 + 1 + 4 + 1 + 1
+        );
+        assert_eq!(
+            count_with_overhead(&human, &MessageOverhead::NONE, words),
+            6
+        );
+
+        let ai = MessageEnum::Ai(AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "get_weather",
+                json!({"city": "Paris"}),
+                Some("call_1".to_string()),
+            )],
+        ));
+        // role + tool name + args + call id
+        assert_eq!(count_with_overhead(&ai, &MessageOverhead::NONE, words), 4);
+    }
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename = "tool_call")]
+pub struct ToolCall {
+    pub name: String,
+
+    #[serde(default = "empty_args")]
+    pub args: Value,
+
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename = "invalid_tool_call")]
+pub struct InvalidToolCall {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub args: Option<String>,
+
+    #[serde(default)]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+fn empty_args() -> Value {
+    Value::Object(Map::new())
+}
+
+impl ToolCall {
+    pub fn new(name: impl Into<String>, args: Value, id: Option<String>) -> Self {
+        ToolCall {
+            name: name.into(),
+            args,
+            id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> &Value {
+        &self.args
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Deserializes the call arguments into a caller-supplied type.
+    pub fn parse_args<'a, T: Deserialize<'a>>(&'a self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.args)
+    }
+
+    /// Builds a tool call from a provider's raw JSON argument string. Calls
+    /// without a name or whose arguments are not a JSON object are returned
+    /// as an `InvalidToolCall` carrying the reason.
+    pub fn try_from_raw(
+        name: Option<String>,
+        args: Option<String>,
+        id: Option<String>,
+    ) -> Result<ToolCall, InvalidToolCall> {
+        let parsed = match args.as_deref().map(str::trim) {
+            None | Some("") => Ok(empty_args()),
+            Some(raw) => serde_json::from_str::<Value>(raw).map_err(|err| err.to_string()),
+        };
+
+        match (name, parsed) {
+            (Some(name), Ok(parsed @ Value::Object(_))) => Ok(ToolCall::new(name, parsed, id)),
+            (name, parsed) => {
+                let error = match parsed {
+                    Err(err) => err,
+                    Ok(_) if name.is_none() => "Tool call is missing a name".to_string(),
+                    Ok(_) => "Tool call arguments must be a JSON object".to_string(),
+                };
+                Err(InvalidToolCall::new(name, args, id, Some(error)))
+            }
+        }
+    }
+}
+
+impl InvalidToolCall {
+    pub fn new(
+        name: Option<String>,
+        args: Option<String>,
+        id: Option<String>,
+        error: Option<String>,
+    ) -> Self {
+        InvalidToolCall {
+            name,
+            args,
+            id,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct WeatherArgs {
+        city: String,
+        unit: Option<String>,
+    }
+
+    #[test]
+    fn test_tool_call_serialization() {
+        let tool_call = ToolCall::new(
+            "get_weather",
+            json!({"city": "Paris"}),
+            Some("call_1".to_string()),
+        );
+
+        let serialized = serde_json::to_value(&tool_call).unwrap();
+        assert_eq!(
+            serialized,
+            json!({
+                "type": "tool_call",
+                "name": "get_weather",
+                "args": {"city": "Paris"},
+                "id": "call_1"
+            })
+        );
+    }
+
+    #[test]
+    fn test_tool_call_deserialization_without_type() {
+        let tool_call: ToolCall =
+            serde_json::from_value(json!({"name": "get_weather", "id": null})).unwrap();
+
+        assert_eq!(tool_call.name(), "get_weather");
+        assert_eq!(tool_call.args(), &json!({}));
+        assert_eq!(tool_call.id(), None);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let tool_call = ToolCall::new(
+            "get_weather",
+            json!({"city": "Paris", "unit": "celsius"}),
+            None,
+        );
+
+        let args: WeatherArgs = tool_call.parse_args().unwrap();
+        assert_eq!(
+            args,
+            WeatherArgs {
+                city: "Paris".to_string(),
+                unit: Some("celsius".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_args_type_mismatch() {
+        let tool_call = ToolCall::new("get_weather", json!({"city": 42}), None);
+
+        let result = tool_call.parse_args::<WeatherArgs>();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_tool_call_round_trip() {
+        let invalid = InvalidToolCall::new(
+            Some("get_weather".to_string()),
+            Some("{\"city\": ".to_string()),
+            Some("call_2".to_string()),
+            Some("EOF while parsing".to_string()),
+        );
+
+        let serialized = serde_json::to_value(&invalid).unwrap();
+        assert_eq!(serialized["type"], "invalid_tool_call");
+
+        let deserialized: InvalidToolCall = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, invalid);
+    }
+
+    #[test]
+    fn test_try_from_raw() {
+        let tool_call = ToolCall::try_from_raw(
+            Some("get_weather".to_string()),
+            Some("{\"city\": \"Paris\"}".to_string()),
+            Some("call_1".to_string()),
+        )
+        .unwrap();
+        assert_eq!(tool_call.args(), &json!({"city": "Paris"}));
+
+        let invalid = ToolCall::try_from_raw(
+            Some("get_weather".to_string()),
+            Some("[1, 2]".to_string()),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            invalid.error.as_deref(),
+            Some("Tool call arguments must be a JSON object")
+        );
+
+        let unnamed = ToolCall::try_from_raw(None, None, None).unwrap_err();
+        assert_eq!(
+            unnamed.error.as_deref(),
+            Some("Tool call is missing a name")
+        );
+    }
+}
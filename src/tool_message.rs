@@ -69,10 +69,10 @@ mod tests {
     #[test]
     fn test_tool_message_serialization_with_values() {
         let mut additional_kwargs = HashMap::new();
-        additional_kwargs.insert("key1".to_string(), "value1".to_string());
+        additional_kwargs.insert("key1".to_string(), "value1".into());
 
         let mut response_metadata = HashMap::new();
-        response_metadata.insert("meta_key".to_string(), "meta_value".to_string());
+        response_metadata.insert("meta_key".to_string(), "meta_value".into());
 
         let mut tool_message = ToolMessage::new(
             "Test message",
@@ -139,7 +139,7 @@ mod tests {
     #[test]
     fn test_tool_message_serialization_with_partial_values() {
         let mut additional_kwargs = HashMap::new();
-        additional_kwargs.insert("key2".to_string(), "value2".to_string());
+        additional_kwargs.insert("key2".to_string(), "value2".into());
 
         let mut tool_message = ToolMessage::new(
             "Partial message",
@@ -0,0 +1,215 @@
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+use crate::MessageEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UsageMetadata {
+    pub input_tokens: u64,
+
+    pub output_tokens: u64,
+
+    pub total_tokens: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub input_token_details: Option<InputTokenDetails>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub output_token_details: Option<OutputTokenDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InputTokenDetails {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub audio: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cache_creation: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cache_read: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputTokenDetails {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub audio: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reasoning: Option<u64>,
+}
+
+impl UsageMetadata {
+    pub fn new(input_tokens: u64, output_tokens: u64) -> Self {
+        UsageMetadata {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            input_token_details: None,
+            output_token_details: None,
+        }
+    }
+
+    pub fn cached_input_tokens(&self) -> u64 {
+        self.input_token_details
+            .and_then(|details| details.cache_read)
+            .unwrap_or(0)
+    }
+
+    pub fn reasoning_tokens(&self) -> u64 {
+        self.output_token_details
+            .and_then(|details| details.reasoning)
+            .unwrap_or(0)
+    }
+
+    pub fn set_cached_input_tokens(&mut self, tokens: u64) {
+        self.input_token_details
+            .get_or_insert_with(Default::default)
+            .cache_read = Some(tokens);
+    }
+
+    pub fn set_reasoning_tokens(&mut self, tokens: u64) {
+        self.output_token_details
+            .get_or_insert_with(Default::default)
+            .reasoning = Some(tokens);
+    }
+
+    /// Sums the usage reported by every `AiMessage` in a conversation.
+    pub fn from_messages(messages: &[MessageEnum]) -> UsageMetadata {
+        messages
+            .iter()
+            .filter_map(MessageEnum::as_ai)
+            .filter_map(|message| message.usage_metadata())
+            .sum()
+    }
+}
+
+fn add_counts(left: Option<u64>, right: Option<u64>) -> Option<u64> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(left + right),
+        (left, right) => left.or(right),
+    }
+}
+
+fn add_details<T: AddAssign>(left: &mut Option<T>, right: Option<T>) {
+    match (left.as_mut(), right) {
+        (Some(left), Some(right)) => *left += right,
+        (None, right) => *left = right,
+        (Some(_), None) => {}
+    }
+}
+
+impl AddAssign for InputTokenDetails {
+    fn add_assign(&mut self, other: InputTokenDetails) {
+        self.audio = add_counts(self.audio, other.audio);
+        self.cache_creation = add_counts(self.cache_creation, other.cache_creation);
+        self.cache_read = add_counts(self.cache_read, other.cache_read);
+    }
+}
+
+impl AddAssign for OutputTokenDetails {
+    fn add_assign(&mut self, other: OutputTokenDetails) {
+        self.audio = add_counts(self.audio, other.audio);
+        self.reasoning = add_counts(self.reasoning, other.reasoning);
+    }
+}
+
+impl AddAssign for UsageMetadata {
+    fn add_assign(&mut self, other: UsageMetadata) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.total_tokens += other.total_tokens;
+        add_details(&mut self.input_token_details, other.input_token_details);
+        add_details(&mut self.output_token_details, other.output_token_details);
+    }
+}
+
+impl Add for UsageMetadata {
+    type Output = UsageMetadata;
+
+    fn add(mut self, other: UsageMetadata) -> UsageMetadata {
+        self += other;
+        self
+    }
+}
+
+impl Sum for UsageMetadata {
+    fn sum<I: Iterator<Item = UsageMetadata>>(iter: I) -> UsageMetadata {
+        iter.fold(UsageMetadata::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a UsageMetadata> for UsageMetadata {
+    fn sum<I: Iterator<Item = &'a UsageMetadata>>(iter: I) -> UsageMetadata {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AiMessage, HumanMessage};
+    use serde_json::json;
+
+    #[test]
+    fn test_usage_metadata_serialization() {
+        let mut usage = UsageMetadata::new(100, 20);
+        usage.set_cached_input_tokens(40);
+        usage.set_reasoning_tokens(5);
+
+        let expected_json = json!({
+            "input_tokens": 100,
+            "output_tokens": 20,
+            "total_tokens": 120,
+            "input_token_details": {"cache_read": 40},
+            "output_token_details": {"reasoning": 5}
+        });
+
+        assert_eq!(serde_json::to_value(usage).unwrap(), expected_json);
+
+        let deserialized: UsageMetadata = serde_json::from_value(expected_json).unwrap();
+        assert_eq!(deserialized, usage);
+        assert_eq!(deserialized.cached_input_tokens(), 40);
+        assert_eq!(deserialized.reasoning_tokens(), 5);
+    }
+
+    #[test]
+    fn test_usage_metadata_add() {
+        let mut first = UsageMetadata::new(10, 5);
+        first.set_cached_input_tokens(4);
+        let mut second = UsageMetadata::new(20, 7);
+        second.set_reasoning_tokens(3);
+
+        let total = first + second;
+        assert_eq!(total.input_tokens, 30);
+        assert_eq!(total.output_tokens, 12);
+        assert_eq!(total.total_tokens, 42);
+        assert_eq!(total.cached_input_tokens(), 4);
+        assert_eq!(total.reasoning_tokens(), 3);
+    }
+
+    #[test]
+    fn test_from_messages_sums_ai_usage() {
+        let mut first = AiMessage::new("First answer");
+        first.set_usage_metadata(Some(UsageMetadata::new(10, 5)));
+        let mut second = AiMessage::new("Second answer");
+        second.set_usage_metadata(Some(UsageMetadata::new(30, 8)));
+
+        let conversation = vec![
+            MessageEnum::Human(HumanMessage::new("Question")),
+            MessageEnum::Ai(first),
+            MessageEnum::Human(HumanMessage::new("Follow-up")),
+            MessageEnum::Ai(second),
+            MessageEnum::Ai(AiMessage::new("No usage reported")),
+        ];
+
+        let total = UsageMetadata::from_messages(&conversation);
+        assert_eq!(total, UsageMetadata::new(40, 13));
+    }
+
+    #[test]
+    fn test_from_messages_empty() {
+        assert_eq!(UsageMetadata::from_messages(&[]), UsageMetadata::default());
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{BaseMessage, MessageEnum};
+
+/// Id of a `RemoveMessage` that clears every message that came before it.
+pub const REMOVE_ALL_MESSAGES: &str = "__remove_all__";
+
+/// Merges `right` into `left` the way a graph state reducer does: messages
+/// whose id matches an existing message replace it in place, messages
+/// targeted by a `RemoveMessage` are dropped, and everything else is appended.
+/// Removal markers for ids that are not present are ignored.
+pub fn add_messages(left: Vec<MessageEnum>, right: Vec<MessageEnum>) -> Vec<MessageEnum> {
+    let (mut merged, right) = match right.iter().rposition(is_remove_all) {
+        Some(position) => (Vec::new(), right.into_iter().skip(position + 1).collect()),
+        None => (left, right),
+    };
+
+    let mut positions: HashMap<String, usize> = merged
+        .iter()
+        .enumerate()
+        .filter_map(|(position, message)| Some((message.id()?.to_string(), position)))
+        .collect();
+    let mut removed = HashSet::new();
+
+    for message in right {
+        let id = message.id().map(str::to_string);
+        match (id, &message) {
+            (Some(id), MessageEnum::Remove(_)) => {
+                removed.insert(id);
+            }
+            (None, MessageEnum::Remove(_)) => {}
+            (Some(id), _) => match positions.get(&id) {
+                Some(&position) => merged[position] = message,
+                None => {
+                    positions.insert(id, merged.len());
+                    merged.push(message);
+                }
+            },
+            (None, _) => merged.push(message),
+        }
+    }
+
+    merged.retain(|message| {
+        !matches!(message, MessageEnum::Remove(_))
+            && !message.id().is_some_and(|id| removed.contains(id))
+    });
+    merged
+}
+
+fn is_remove_all(message: &MessageEnum) -> bool {
+    matches!(message, MessageEnum::Remove(_)) && message.id() == Some(REMOVE_ALL_MESSAGES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AiMessage, HumanMessage, RemoveMessage};
+
+    fn human(id: &str, content: &str) -> MessageEnum {
+        let mut message = HumanMessage::new(content);
+        message.set_id(Some(id.to_string()));
+        MessageEnum::Human(message)
+    }
+
+    fn ai(id: &str, content: &str) -> MessageEnum {
+        let mut message = AiMessage::new(content);
+        message.set_id(Some(id.to_string()));
+        MessageEnum::Ai(message)
+    }
+
+    #[test]
+    fn test_add_messages_appends() {
+        let left = vec![human("1", "Hi")];
+        let right = vec![
+            ai("2", "Hello!"),
+            MessageEnum::Human(HumanMessage::new("No id")),
+        ];
+
+        let merged = add_messages(left, right);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[1].id(), Some("2"));
+        assert_eq!(merged[2].content(), "No id");
+    }
+
+    #[test]
+    fn test_add_messages_replaces_by_id() {
+        let left = vec![human("1", "Hi"), ai("2", "Hello!")];
+        let right = vec![ai("2", "Hello, how can I help?")];
+
+        let merged = add_messages(left, right);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].content(), "Hello, how can I help?");
+    }
+
+    #[test]
+    fn test_add_messages_removes_by_id() {
+        let left = vec![human("1", "Hi"), ai("2", "Hello!"), human("3", "Bye")];
+        let right = vec![
+            MessageEnum::Remove(RemoveMessage::with_id("2")),
+            MessageEnum::Remove(RemoveMessage::with_id("missing")),
+        ];
+
+        let merged = add_messages(left, right);
+        assert_eq!(merged, vec![human("1", "Hi"), human("3", "Bye")]);
+    }
+
+    #[test]
+    fn test_add_messages_removes_new_message_in_same_update() {
+        let right = vec![
+            ai("2", "Draft"),
+            MessageEnum::Remove(RemoveMessage::with_id("2")),
+        ];
+
+        let merged = add_messages(vec![human("1", "Hi")], right);
+        assert_eq!(merged, vec![human("1", "Hi")]);
+    }
+
+    #[test]
+    fn test_add_messages_remove_all() {
+        let left = vec![human("1", "Hi"), ai("2", "Hello!")];
+        let right = vec![
+            human("0", "Ignored"),
+            MessageEnum::Remove(RemoveMessage::with_id(REMOVE_ALL_MESSAGES)),
+            human("3", "Fresh start"),
+        ];
+
+        let merged = add_messages(left, right);
+        assert_eq!(merged, vec![human("3", "Fresh start")]);
+    }
+}
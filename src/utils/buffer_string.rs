@@ -0,0 +1,248 @@
+use std::fmt;
+
+use crate::{BaseMessage, MessageEnum};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BufferStringError {
+    /// A chat message role matches a standard or configured prefix, or
+    /// contains `": "` or a line break, so it would not read back as a chat
+    /// message with that role.
+    AmbiguousChatRole { index: usize, role: String },
+}
+
+impl fmt::Display for BufferStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BufferStringError::AmbiguousChatRole { index, role } => write!(
+                f,
+                "Chat role {:?} of message {} cannot be told apart from another prefix",
+                role, index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BufferStringError {}
+
+/// Role prefixes used by [`get_buffer_string`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferStringOptions {
+    human_prefix: String,
+    ai_prefix: String,
+    system_prefix: String,
+    tool_prefix: String,
+}
+
+impl BufferStringOptions {
+    pub fn new() -> Self {
+        BufferStringOptions::default()
+    }
+
+    pub fn human_prefix(&self) -> &str {
+        &self.human_prefix
+    }
+
+    pub fn ai_prefix(&self) -> &str {
+        &self.ai_prefix
+    }
+
+    pub fn system_prefix(&self) -> &str {
+        &self.system_prefix
+    }
+
+    pub fn tool_prefix(&self) -> &str {
+        &self.tool_prefix
+    }
+
+    pub fn set_human_prefix(&mut self, prefix: impl Into<String>) {
+        self.human_prefix = prefix.into();
+    }
+
+    pub fn set_ai_prefix(&mut self, prefix: impl Into<String>) {
+        self.ai_prefix = prefix.into();
+    }
+
+    pub fn set_system_prefix(&mut self, prefix: impl Into<String>) {
+        self.system_prefix = prefix.into();
+    }
+
+    pub fn set_tool_prefix(&mut self, prefix: impl Into<String>) {
+        self.tool_prefix = prefix.into();
+    }
+}
+
+impl Default for BufferStringOptions {
+    fn default() -> Self {
+        BufferStringOptions {
+            human_prefix: "Human".to_string(),
+            ai_prefix: "AI".to_string(),
+            system_prefix: "System".to_string(),
+            tool_prefix: "Tool".to_string(),
+        }
+    }
+}
+
+/// Renders messages as a `role: content` transcript, one message per line.
+///
+/// Chat messages use their own role as the prefix and tool messages are
+/// written as `Tool: <tool_call_id>: <content>`. `RemoveMessage`s carry no
+/// conversation content and are skipped. With the default prefixes the
+/// output of single-line messages reads back through
+/// `MessageEnum::parse_messages`; `TranscriptParser` also reads back custom
+/// prefixes and multi-line content.
+///
+/// The prefix is all that identifies a message, so a chat message whose role
+/// matches a standard or configured prefix, ignoring case, or that contains
+/// `": "` or a line break, is rejected with
+/// [`BufferStringError::AmbiguousChatRole`] rather than written in a form
+/// that reads back as a different message.
+pub fn get_buffer_string(
+    messages: &[MessageEnum],
+    options: &BufferStringOptions,
+) -> Result<String, BufferStringError> {
+    let mut buffer = String::new();
+
+    for (index, message) in messages.iter().enumerate() {
+        let prefix = match message {
+            MessageEnum::Human(_) => options.human_prefix(),
+            MessageEnum::Ai(_) => options.ai_prefix(),
+            MessageEnum::System(_) => options.system_prefix(),
+            MessageEnum::Tool(_) => options.tool_prefix(),
+            MessageEnum::Chat(message) => {
+                let role = message.role();
+                if is_ambiguous_role(role, options) {
+                    return Err(BufferStringError::AmbiguousChatRole {
+                        index,
+                        role: role.to_string(),
+                    });
+                }
+                role
+            }
+            MessageEnum::Remove(_) => continue,
+        };
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(prefix);
+        buffer.push_str(": ");
+        if let MessageEnum::Tool(tool) = message {
+            buffer.push_str(tool.tool_call_id());
+            buffer.push_str(": ");
+        }
+        buffer.push_str(&message.content());
+    }
+    Ok(buffer)
+}
+
+fn is_ambiguous_role(role: &str, options: &BufferStringOptions) -> bool {
+    role.contains(": ")
+        || role.contains(['\n', '\r'])
+        || ["human", "ai", "system", "tool"]
+            .into_iter()
+            .chain([
+                options.human_prefix(),
+                options.ai_prefix(),
+                options.system_prefix(),
+                options.tool_prefix(),
+            ])
+            .any(|prefix| prefix.eq_ignore_ascii_case(role))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_message::ToolStatus;
+    use crate::{AiMessage, ChatMessage, HumanMessage, RemoveMessage, SystemMessage, ToolMessage};
+
+    fn conversation() -> Vec<MessageEnum> {
+        vec![
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+            MessageEnum::Human(HumanMessage::new("Weather in Paris?")),
+            MessageEnum::Ai(AiMessage::new("Checking.")),
+            MessageEnum::Tool(ToolMessage::new(
+                "18 degrees",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+            MessageEnum::Remove(RemoveMessage::with_id("msg_0")),
+            MessageEnum::Chat(ChatMessage::new("Looks right.", "critic".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_default_prefixes() {
+        let buffer = get_buffer_string(&conversation(), &BufferStringOptions::default()).unwrap();
+        assert_eq!(
+            buffer,
+            "System: Be brief.\n\
+             Human: Weather in Paris?\n\
+             AI: Checking.\n\
+             Tool: call_1: 18 degrees\n\
+             critic: Looks right."
+        );
+    }
+
+    #[test]
+    fn test_custom_prefixes() {
+        let mut options = BufferStringOptions::new();
+        options.set_human_prefix("User");
+        options.set_ai_prefix("Assistant");
+
+        let buffer = get_buffer_string(&conversation()[1..3], &options).unwrap();
+        assert_eq!(buffer, "User: Weather in Paris?\nAssistant: Checking.");
+        assert_eq!(get_buffer_string(&[], &options).unwrap(), "");
+    }
+
+    #[test]
+    fn test_round_trip_through_parse_messages() {
+        let messages = conversation();
+        let buffer = get_buffer_string(&messages, &BufferStringOptions::default()).unwrap();
+
+        let parsed = MessageEnum::parse_messages(&buffer).unwrap();
+        let expected: Vec<MessageEnum> = messages
+            .into_iter()
+            .filter(|message| !matches!(message, MessageEnum::Remove(_)))
+            .collect();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_chat_role_matching_a_prefix_is_rejected() {
+        let options = BufferStringOptions::default();
+        for (role, index) in [("human", 1), ("AI", 1), ("System", 1), ("tool", 1)] {
+            let messages = vec![
+                MessageEnum::Chat(ChatMessage::new("Hi", "critic".to_string())),
+                MessageEnum::Chat(ChatMessage::new("Hi", role.to_string())),
+            ];
+            assert_eq!(
+                get_buffer_string(&messages, &options),
+                Err(BufferStringError::AmbiguousChatRole {
+                    index,
+                    role: role.to_string()
+                })
+            );
+        }
+
+        let mut options = BufferStringOptions::new();
+        options.set_human_prefix("User");
+        let user = MessageEnum::Chat(ChatMessage::new("Hi", "user".to_string()));
+        assert!(get_buffer_string(std::slice::from_ref(&user), &options).is_err());
+
+        let human = MessageEnum::Chat(ChatMessage::new("Hi", "human".to_string()));
+        let err = get_buffer_string(&[human], &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Chat role \"human\" of message 0 cannot be told apart from another prefix"
+        );
+
+        let split = MessageEnum::Chat(ChatMessage::new("Hi", "a: b".to_string()));
+        assert!(get_buffer_string(&[split], &options).is_err());
+
+        let buffer =
+            get_buffer_string(std::slice::from_ref(&user), &BufferStringOptions::default())
+                .unwrap();
+        assert_eq!(MessageEnum::parse_messages(&buffer).unwrap(), vec![user]);
+    }
+}
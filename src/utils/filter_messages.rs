@@ -0,0 +1,255 @@
+use std::borrow::Borrow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseMessage, MessageEnum, MessageType};
+
+/// Selects messages by type, name, id and example flag.
+///
+/// A message passes when it matches none of the `exclude_*` criteria and,
+/// if any `include_*` criterion is set, matches at least one of them. An
+/// empty filter passes everything. Filters serialize to JSON so they can be
+/// kept in configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageFilter {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include_types: Vec<MessageType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude_types: Vec<MessageType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include_names: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude_names: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude_ids: Vec<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub include_examples: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub exclude_examples: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl MessageFilter {
+    pub fn new() -> Self {
+        MessageFilter::default()
+    }
+
+    pub fn matches(&self, message: &MessageEnum) -> bool {
+        let message_type = message.message_type();
+        let name = message.name();
+        let id = message.id();
+
+        let excluded = self.exclude_types.contains(message_type)
+            || contains(&self.exclude_names, name)
+            || contains(&self.exclude_ids, id)
+            || (self.exclude_examples && message.is_example());
+        if excluded {
+            return false;
+        }
+
+        let has_includes = !self.include_types.is_empty()
+            || !self.include_names.is_empty()
+            || !self.include_ids.is_empty()
+            || self.include_examples;
+
+        !has_includes
+            || self.include_types.contains(message_type)
+            || contains(&self.include_names, name)
+            || contains(&self.include_ids, id)
+            || (self.include_examples && message.is_example())
+    }
+}
+
+fn contains(values: &[String], value: Option<&str>) -> bool {
+    value.is_some_and(|value| values.iter().any(|candidate| candidate == value))
+}
+
+/// Returns clones of the messages that pass `filter`, in order.
+pub fn filter_messages(messages: &[MessageEnum], filter: &MessageFilter) -> Vec<MessageEnum> {
+    messages.iter().filter_messages(filter).cloned().collect()
+}
+
+/// Iterator returned by [`FilterMessagesExt::filter_messages`].
+#[derive(Debug, Clone)]
+pub struct FilterMessages<'f, I> {
+    iter: I,
+    filter: &'f MessageFilter,
+}
+
+impl<I> Iterator for FilterMessages<'_, I>
+where
+    I: Iterator,
+    I::Item: Borrow<MessageEnum>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.iter.find(|message| filter.matches(message.borrow()))
+    }
+}
+
+/// Adds `filter_messages` to iterators over owned or borrowed messages.
+pub trait FilterMessagesExt: Iterator + Sized {
+    fn filter_messages(self, filter: &MessageFilter) -> FilterMessages<'_, Self> {
+        FilterMessages { iter: self, filter }
+    }
+}
+
+impl<I> FilterMessagesExt for I
+where
+    I: Iterator,
+    I::Item: Borrow<MessageEnum>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_message::ToolStatus;
+    use crate::{AiMessage, HumanMessage, SystemMessage, ToolCall, ToolMessage};
+    use serde_json::json;
+
+    fn conversation() -> Vec<MessageEnum> {
+        let mut researcher = AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "search",
+                json!({"q": "rust"}),
+                Some("call_1".to_string()),
+            )],
+        );
+        researcher.set_name(Some("researcher".to_string()));
+        researcher.set_id(Some("msg_2".to_string()));
+
+        let mut writer = AiMessage::new("Rust is a systems language.");
+        writer.set_name(Some("writer".to_string()));
+        writer.set_id(Some("msg_4".to_string()));
+
+        vec![
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+            MessageEnum::Human(HumanMessage::new_with_example("2 + 2", true)),
+            MessageEnum::Ai(AiMessage::new_with_example("4", true)),
+            MessageEnum::Human(HumanMessage::new("What is Rust?")),
+            MessageEnum::Ai(researcher),
+            MessageEnum::Tool(ToolMessage::new(
+                "Rust is a language.",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+            MessageEnum::Ai(writer),
+        ]
+    }
+
+    #[test]
+    fn test_empty_filter_keeps_everything() {
+        let messages = conversation();
+        assert_eq!(filter_messages(&messages, &MessageFilter::new()), messages);
+    }
+
+    #[test]
+    fn test_exclude_tool_traffic() {
+        let filter = MessageFilter {
+            exclude_types: vec![MessageType::Tool, MessageType::System],
+            exclude_examples: true,
+            ..MessageFilter::default()
+        };
+
+        let filtered = filter_messages(&conversation(), &filter);
+        assert_eq!(filtered.len(), 3);
+        assert!(filtered
+            .iter()
+            .all(|message| !matches!(message, MessageEnum::Tool(_) | MessageEnum::System(_))));
+    }
+
+    #[test]
+    fn test_includes_are_combined_and_excludes_win() {
+        let filter = MessageFilter {
+            include_names: vec!["researcher".to_string()],
+            include_types: vec![MessageType::Human],
+            exclude_examples: true,
+            ..MessageFilter::default()
+        };
+
+        let filtered = filter_messages(&conversation(), &filter);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].content(), "What is Rust?");
+        assert_eq!(filtered[1].name(), Some("researcher"));
+
+        let filter = MessageFilter {
+            include_ids: vec!["msg_2".to_string(), "msg_4".to_string()],
+            exclude_names: vec!["writer".to_string()],
+            ..MessageFilter::default()
+        };
+        let filtered = filter_messages(&conversation(), &filter);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].id(), Some("msg_2"));
+    }
+
+    #[test]
+    fn test_only_examples() {
+        let filter = MessageFilter {
+            include_examples: true,
+            ..MessageFilter::default()
+        };
+
+        let filtered = filter_messages(&conversation(), &filter);
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered.iter().all(|message| message.is_example()));
+    }
+
+    #[test]
+    fn test_iterator_adapter() {
+        let filter = MessageFilter {
+            include_names: vec!["writer".to_string()],
+            ..MessageFilter::default()
+        };
+
+        let messages = conversation();
+        let borrowed: Vec<&MessageEnum> = messages.iter().filter_messages(&filter).collect();
+        assert_eq!(borrowed, vec![&messages[6]]);
+
+        let owned: Vec<MessageEnum> = conversation()
+            .into_iter()
+            .filter_messages(&filter)
+            .collect();
+        assert_eq!(owned.len(), 1);
+        assert_eq!(owned[0].content(), "Rust is a systems language.");
+    }
+
+    #[test]
+    fn test_filter_serialization() {
+        let filter = MessageFilter {
+            exclude_types: vec![MessageType::Tool],
+            include_names: vec!["writer".to_string()],
+            exclude_examples: true,
+            ..MessageFilter::default()
+        };
+
+        let value = serde_json::to_value(&filter).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "exclude_types": ["Tool"],
+                "include_names": ["writer"],
+                "exclude_examples": true
+            })
+        );
+
+        let parsed: MessageFilter =
+            serde_json::from_value(json!({"exclude_types": ["Tool"], "include_names": ["writer"], "exclude_examples": true}))
+                .unwrap();
+        assert_eq!(parsed, filter);
+        assert_eq!(
+            serde_json::from_str::<MessageFilter>("{}").unwrap(),
+            MessageFilter::new()
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{BaseMessage, BaseMessageFields, MessageContent, MessageEnum};
+
+/// Merges runs of consecutive messages of the same type into one message.
+///
+/// Content is joined with `separator`, metadata maps are merged key by key
+/// and AI tool calls and usage are combined. The first
+/// message of a run keeps its id and name. Chat messages merge only when
+/// their roles match. `ToolMessage`s and `RemoveMessage`s are never merged
+/// since each refers to a distinct tool call or message.
+pub fn merge_message_runs(messages: &[MessageEnum], separator: &str) -> Vec<MessageEnum> {
+    let mut merged: Vec<MessageEnum> = Vec::with_capacity(messages.len());
+
+    for message in messages.iter().cloned() {
+        let message = match merged.last_mut() {
+            Some(last) => match merge_into(last, message, separator) {
+                Some(message) => message,
+                None => continue,
+            },
+            None => message,
+        };
+        merged.push(message);
+    }
+    merged
+}
+
+/// Merges `next` into `last`, handing `next` back if the two cannot merge.
+fn merge_into(last: &mut MessageEnum, next: MessageEnum, separator: &str) -> Option<MessageEnum> {
+    match (last, next) {
+        (MessageEnum::Ai(last), MessageEnum::Ai(next)) => {
+            merge_base(&mut last.base, next.base, separator);
+            last.tool_calls.extend(next.tool_calls);
+            last.invalid_tool_calls.extend(next.invalid_tool_calls);
+            last.usage_metadata = match (last.usage_metadata.take(), next.usage_metadata) {
+                (Some(left), Some(right)) => Some(left + right),
+                (left, right) => left.or(right),
+            };
+        }
+        (MessageEnum::Human(last), MessageEnum::Human(next)) => {
+            merge_base(&mut last.base, next.base, separator)
+        }
+        (MessageEnum::System(last), MessageEnum::System(next)) => {
+            merge_base(&mut last.base, next.base, separator)
+        }
+        (MessageEnum::Chat(last), MessageEnum::Chat(next)) if last.role() == next.role() => {
+            merge_base(last.base_mut(), next.into_base(), separator)
+        }
+        (_, next) => return Some(next),
+    }
+    None
+}
+
+fn merge_base(left: &mut BaseMessageFields, right: BaseMessageFields, separator: &str) {
+    if !left.content.is_empty() && !right.content.is_empty() {
+        left.content += MessageContent::Text(separator.to_string());
+    }
+    left.content += right.content;
+    merge_metadata(&mut left.additional_kwargs, right.additional_kwargs);
+    merge_metadata(&mut left.response_metadata, right.response_metadata);
+    if left.id.is_none() {
+        left.id = right.id;
+    }
+    if left.name.is_none() {
+        left.name = right.name;
+    }
+}
+
+/// Unlike streamed chunks, each message carries complete values, so strings
+/// such as `finish_reason` are not concatenated. The first message's scalar
+/// wins unless it is `null`, objects are merged recursively and arrays are
+/// extended.
+fn merge_metadata(left: &mut HashMap<String, Value>, right: HashMap<String, Value>) {
+    for (key, value) in right {
+        match left.get_mut(&key) {
+            Some(existing) => merge_metadata_value(existing, value),
+            None => {
+                left.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_metadata_value(left: &mut Value, right: Value) {
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            for (key, value) in right {
+                match left.get_mut(&key) {
+                    Some(existing) => merge_metadata_value(existing, value),
+                    None => {
+                        left.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(left), Value::Array(right)) => left.extend(right),
+        (left @ Value::Null, right) => *left = right,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::openai::{from_openai_completion, OpenAiChatCompletion};
+    use crate::tool_message::ToolStatus;
+    use crate::{
+        AiMessage, ChatMessage, ContentBlock, HumanMessage, RemoveMessage, SystemMessage, ToolCall,
+        ToolMessage, UsageMetadata,
+    };
+    use serde_json::json;
+
+    fn tool_result(content: &str, tool_call_id: &str) -> MessageEnum {
+        MessageEnum::Tool(ToolMessage::new(
+            content,
+            tool_call_id.to_string(),
+            None,
+            ToolStatus::Success,
+        ))
+    }
+
+    #[test]
+    fn test_merge_human_runs() {
+        let mut first = HumanMessage::new("Hi");
+        first.set_id(Some("msg_1".to_string()));
+        let mut second = HumanMessage::new("Are you there?");
+        second.set_id(Some("msg_2".to_string()));
+        second.set_name(Some("ada".to_string()));
+        second.insert_additional_kwarg("source", "web").unwrap();
+
+        let messages = vec![
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+            MessageEnum::Human(first),
+            MessageEnum::Human(HumanMessage::new("")),
+            MessageEnum::Human(second),
+            MessageEnum::Ai(AiMessage::new("Yes.")),
+        ];
+
+        let merged = merge_message_runs(&messages, "\n");
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[1].content(), "Hi\nAre you there?");
+        assert_eq!(merged[1].id(), Some("msg_1"));
+        assert_eq!(merged[1].name(), Some("ada"));
+        assert_eq!(
+            merged[1].additional_kwargs().get("source"),
+            Some(&json!("web"))
+        );
+    }
+
+    #[test]
+    fn test_merge_ai_tool_calls_and_usage() {
+        let mut first = AiMessage::new_with_tool_calls(
+            "Checking.",
+            vec![ToolCall::new(
+                "search",
+                json!({"q": "a"}),
+                Some("call_1".to_string()),
+            )],
+        );
+        first.set_usage_metadata(Some(UsageMetadata::new(10, 5)));
+        let mut second = AiMessage::new_with_tool_calls(
+            "",
+            vec![ToolCall::new(
+                "search",
+                json!({"q": "b"}),
+                Some("call_2".to_string()),
+            )],
+        );
+        second.set_usage_metadata(Some(UsageMetadata::new(20, 7)));
+
+        let messages = vec![
+            MessageEnum::Ai(first),
+            MessageEnum::Ai(second),
+            tool_result("A", "call_1"),
+            tool_result("B", "call_2"),
+        ];
+
+        let merged = merge_message_runs(&messages, " ");
+        assert_eq!(merged.len(), 3);
+
+        let ai = merged[0].as_ai().unwrap();
+        assert_eq!(ai.content(), "Checking.");
+        assert_eq!(ai.tool_calls().len(), 2);
+        assert_eq!(ai.usage_metadata(), Some(&UsageMetadata::new(30, 12)));
+
+        assert_eq!(&merged[1..], &messages[2..]);
+    }
+
+    #[test]
+    fn test_merge_block_content() {
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new(vec![
+                ContentBlock::text("Look at this"),
+                ContentBlock::image_url("https://example.com/cat.png"),
+            ])),
+            MessageEnum::Human(HumanMessage::new("What is it?")),
+        ];
+
+        let merged = merge_message_runs(&messages, "\n\n");
+        assert_eq!(merged.len(), 1);
+        assert_eq!(
+            merged[0].message_content().blocks().into_owned(),
+            vec![
+                ContentBlock::text("Look at this"),
+                ContentBlock::image_url("https://example.com/cat.png"),
+                ContentBlock::text("\n\nWhat is it?"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chat_roles_and_removals_stay_separate() {
+        let messages = vec![
+            MessageEnum::Chat(ChatMessage::new("One", "critic".to_string())),
+            MessageEnum::Chat(ChatMessage::new("Two", "critic".to_string())),
+            MessageEnum::Chat(ChatMessage::new("Three", "editor".to_string())),
+            MessageEnum::Remove(RemoveMessage::with_id("msg_1")),
+            MessageEnum::Remove(RemoveMessage::with_id("msg_2")),
+        ];
+
+        let merged = merge_message_runs(&messages, " ");
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged[0].content(), "One Two");
+        assert_eq!(merged[1].role(), "editor");
+    }
+
+    #[test]
+    fn test_merge_keeps_complete_metadata_values() {
+        let completion: OpenAiChatCompletion = serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "model": "gpt-4o-2024-08-06",
+            "system_fingerprint": "fp_5050236cbd",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hello."},
+                "finish_reason": "stop"
+            }]
+        }))
+        .unwrap();
+        let mut first = from_openai_completion(&completion).unwrap();
+        first
+            .insert_additional_kwarg("annotations", json!([{"type": "a"}]))
+            .unwrap();
+        first
+            .insert_additional_kwarg("audio", json!({"id": "audio_1", "data": null}))
+            .unwrap();
+        let mut second = from_openai_completion(&completion).unwrap();
+        second
+            .insert_additional_kwarg("annotations", json!([{"type": "b"}]))
+            .unwrap();
+        second
+            .insert_additional_kwarg("audio", json!({"data": "UklGRg==", "format": "wav"}))
+            .unwrap();
+
+        let merged = merge_message_runs(&[MessageEnum::Ai(first), MessageEnum::Ai(second)], " ");
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].content(), "Hello. Hello.");
+
+        let metadata = merged[0].response_metadata();
+        assert_eq!(metadata["finish_reason"], json!("stop"));
+        assert_eq!(metadata["system_fingerprint"], json!("fp_5050236cbd"));
+        assert_eq!(metadata["model_name"], json!("gpt-4o-2024-08-06"));
+
+        let kwargs = merged[0].additional_kwargs();
+        assert_eq!(kwargs["annotations"], json!([{"type": "a"}, {"type": "b"}]));
+        assert_eq!(
+            kwargs["audio"],
+            json!({"id": "audio_1", "data": "UklGRg==", "format": "wav"})
+        );
+    }
+}
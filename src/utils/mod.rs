@@ -0,0 +1,14 @@
+pub mod add_messages;
+pub mod buffer_string;
+pub mod filter_messages;
+pub mod merge_message_runs;
+pub mod transcript;
+pub mod trim_messages;
+pub mod validate_conversation;
+pub use add_messages::{add_messages, REMOVE_ALL_MESSAGES};
+pub use buffer_string::{get_buffer_string, BufferStringError, BufferStringOptions};
+pub use filter_messages::{filter_messages, FilterMessages, FilterMessagesExt, MessageFilter};
+pub use merge_message_runs::merge_message_runs;
+pub use transcript::{TranscriptError, TranscriptParser};
+pub use trim_messages::{trim_messages, TrimOptions, TrimStrategy};
+pub use validate_conversation::{validate_conversation, Diagnostic, Rule, RuleSet, Severity};
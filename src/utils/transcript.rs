@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::tool_message::ToolStatus;
+use crate::{AiMessage, ChatMessage, HumanMessage, MessageEnum, SystemMessage, ToolMessage};
+
+use super::BufferStringOptions;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptError {
+    MissingRole { line: usize, text: String },
+    MissingToolCallId { line: usize, text: String },
+}
+
+impl TranscriptError {
+    /// The 1-based line the error was found on.
+    pub fn line(&self) -> usize {
+        match self {
+            TranscriptError::MissingRole { line, .. }
+            | TranscriptError::MissingToolCallId { line, .. } => *line,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            TranscriptError::MissingRole { text, .. }
+            | TranscriptError::MissingToolCallId { text, .. } => text,
+        }
+    }
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::MissingRole { line, text } => {
+                write!(f, "Line {}: expected a role prefix: {:?}", line, text)
+            }
+            TranscriptError::MissingToolCallId { line, text } => {
+                write!(f, "Line {}: expected a tool call id: {:?}", line, text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Role {
+    Human,
+    Ai,
+    System,
+    Tool,
+    Chat(String),
+}
+
+/// Parses `role: content` transcripts whose messages may span several lines.
+///
+/// A message starts only at a line beginning with a recognised prefix
+/// followed by `": "`, or a line holding just the prefix and a colon. Every
+/// other line continues the previous message, so content keeps its newlines
+/// and blank lines; blank lines at the end of a message are dropped. Prefixes
+/// are matched case-insensitively. Tool messages are written as
+/// `tool: <tool_call_id>: <content>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptParser {
+    prefixes: HashMap<String, Role>,
+}
+
+impl TranscriptParser {
+    /// Recognises `human`, `ai`, `system` and `tool`.
+    pub fn new() -> Self {
+        let prefixes = [
+            ("human", Role::Human),
+            ("ai", Role::Ai),
+            ("system", Role::System),
+            ("tool", Role::Tool),
+        ]
+        .into_iter()
+        .map(|(prefix, role)| (prefix.to_string(), role))
+        .collect();
+        TranscriptParser { prefixes }
+    }
+
+    /// Also recognises the prefixes `get_buffer_string` writes with
+    /// `options`.
+    pub fn with_prefixes(options: &BufferStringOptions) -> Self {
+        let mut parser = TranscriptParser::new();
+        parser.insert(options.human_prefix(), Role::Human);
+        parser.insert(options.ai_prefix(), Role::Ai);
+        parser.insert(options.system_prefix(), Role::System);
+        parser.insert(options.tool_prefix(), Role::Tool);
+        parser
+    }
+
+    /// Recognises `role` as the start of a `ChatMessage` with that role.
+    pub fn add_chat_role(&mut self, role: impl Into<String>) {
+        let role = role.into();
+        self.prefixes.insert(role.to_lowercase(), Role::Chat(role));
+    }
+
+    fn insert(&mut self, prefix: &str, role: Role) {
+        self.prefixes.insert(prefix.to_lowercase(), role);
+    }
+
+    fn match_prefix<'a>(&self, line: &'a str) -> Option<(&Role, &'a str)> {
+        let (prefix, rest) = match line.split_once(": ") {
+            Some((prefix, rest)) => (prefix, rest),
+            None => (line.strip_suffix(':')?, ""),
+        };
+        let role = self.prefixes.get(&prefix.to_lowercase())?;
+        Some((role, rest))
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Vec<MessageEnum>, TranscriptError> {
+        let mut messages = Vec::new();
+        let mut current: Option<PendingMessage> = None;
+
+        for (index, line) in input.lines().enumerate() {
+            let number = index + 1;
+            if let Some((role, rest)) = self.match_prefix(line) {
+                if let Some(pending) = current.take() {
+                    messages.push(pending.finish());
+                }
+                current = Some(PendingMessage::start(role, rest, number, line)?);
+                continue;
+            }
+
+            match current.as_mut() {
+                Some(pending) => pending.lines.push(line),
+                None if line.trim().is_empty() => {}
+                None => {
+                    return Err(TranscriptError::MissingRole {
+                        line: number,
+                        text: line.to_string(),
+                    })
+                }
+            }
+        }
+
+        if let Some(pending) = current {
+            messages.push(pending.finish());
+        }
+        Ok(messages)
+    }
+}
+
+impl Default for TranscriptParser {
+    fn default() -> Self {
+        TranscriptParser::new()
+    }
+}
+
+struct PendingMessage<'a> {
+    role: Role,
+    tool_call_id: Option<&'a str>,
+    lines: Vec<&'a str>,
+}
+
+impl<'a> PendingMessage<'a> {
+    fn start(role: &Role, rest: &'a str, line: usize, text: &str) -> Result<Self, TranscriptError> {
+        let (tool_call_id, first) = match role {
+            Role::Tool => {
+                let (id, first) = rest
+                    .split_once(": ")
+                    .or_else(|| Some((rest.strip_suffix(':')?, "")))
+                    .filter(|(id, _)| !id.is_empty())
+                    .ok_or_else(|| TranscriptError::MissingToolCallId {
+                        line,
+                        text: text.to_string(),
+                    })?;
+                (Some(id), first)
+            }
+            _ => (None, rest),
+        };
+
+        Ok(PendingMessage {
+            role: role.clone(),
+            tool_call_id,
+            lines: if first.is_empty() {
+                Vec::new()
+            } else {
+                vec![first]
+            },
+        })
+    }
+
+    fn finish(mut self) -> MessageEnum {
+        while self.lines.last().is_some_and(|line| line.trim().is_empty()) {
+            self.lines.pop();
+        }
+        let content = self.lines.join("\n");
+
+        match self.role {
+            Role::Human => MessageEnum::Human(HumanMessage::new(content)),
+            Role::Ai => MessageEnum::Ai(AiMessage::new(content)),
+            Role::System => MessageEnum::System(SystemMessage::new(content)),
+            Role::Tool => MessageEnum::Tool(ToolMessage::new(
+                content,
+                self.tool_call_id.unwrap_or_default().to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+            Role::Chat(role) => MessageEnum::Chat(ChatMessage::new(content, role)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::get_buffer_string;
+    use crate::BaseMessage;
+
+    #[test]
+    fn test_multi_line_messages() {
+        let input = "System: Answer in two parts.\n\
+                     Human: Explain this:\n\
+                     \n\
+                     fn main() {}\n\
+                     Note: it compiles.\n\
+                     \n\
+                     AI:\n\
+                     Part one.\n\
+                     \n\
+                     Part two.\n\
+                     \n";
+
+        let messages = TranscriptParser::new().parse(input).unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].content(), "Answer in two parts.");
+        assert_eq!(
+            messages[1].content(),
+            "Explain this:\n\nfn main() {}\nNote: it compiles."
+        );
+        assert!(matches!(messages[2], MessageEnum::Ai(_)));
+        assert_eq!(messages[2].content(), "Part one.\n\nPart two.");
+    }
+
+    #[test]
+    fn test_tool_messages() {
+        let input = "tool: call_1: line one\nline two\nTOOL: call_2:\n{\"ok\": true}";
+
+        let messages = TranscriptParser::new().parse(input).unwrap();
+        assert_eq!(messages.len(), 2);
+        let first = messages[0].as_tool().unwrap();
+        assert_eq!(first.tool_call_id(), "call_1");
+        assert_eq!(first.content(), "line one\nline two");
+        let second = messages[1].as_tool().unwrap();
+        assert_eq!(second.tool_call_id(), "call_2");
+        assert_eq!(second.content(), "{\"ok\": true}");
+    }
+
+    #[test]
+    fn test_errors_carry_line_and_text() {
+        let err = TranscriptParser::new()
+            .parse("\nnot a message\nHuman: hi")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            TranscriptError::MissingRole {
+                line: 2,
+                text: "not a message".to_string()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Line 2: expected a role prefix: \"not a message\""
+        );
+
+        let err = TranscriptParser::new()
+            .parse("Human: hi\nTool: no id here")
+            .unwrap_err();
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.text(), "Tool: no id here");
+    }
+
+    #[test]
+    fn test_chat_roles_are_opt_in() {
+        let input = "Human: hi\ncritic: too short";
+
+        let messages = TranscriptParser::new().parse(input).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content(), "hi\ncritic: too short");
+
+        let mut parser = TranscriptParser::new();
+        parser.add_chat_role("critic");
+        let messages = parser.parse(input).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].role(), "critic");
+    }
+
+    #[test]
+    fn test_round_trip_with_buffer_string() {
+        let mut options = BufferStringOptions::new();
+        options.set_human_prefix("User");
+        options.set_ai_prefix("Assistant");
+
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("Write a haiku.\n\nAbout rust.")),
+            MessageEnum::Ai(AiMessage::new("Iron slowly blooms\nred")),
+            MessageEnum::Tool(ToolMessage::new(
+                "{\n  \"ok\": true\n}",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+            MessageEnum::Chat(ChatMessage::new("Nice.", "critic".to_string())),
+        ];
+        let buffer = get_buffer_string(&messages, &options).unwrap();
+
+        let mut parser = TranscriptParser::with_prefixes(&options);
+        parser.add_chat_role("critic");
+        assert_eq!(parser.parse(&buffer).unwrap(), messages);
+    }
+}
@@ -0,0 +1,305 @@
+use std::ops::Range;
+
+use crate::tokens::TokenCounter;
+use crate::MessageEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrimStrategy {
+    /// Keep the earliest messages that fit.
+    First,
+    /// Keep the most recent messages that fit.
+    #[default]
+    Last,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrimOptions {
+    max_tokens: usize,
+    strategy: TrimStrategy,
+    include_system: bool,
+    start_on_human: bool,
+}
+
+impl TrimOptions {
+    pub fn new(max_tokens: usize) -> Self {
+        TrimOptions {
+            max_tokens,
+            strategy: TrimStrategy::default(),
+            include_system: false,
+            start_on_human: false,
+        }
+    }
+
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    pub fn strategy(&self) -> TrimStrategy {
+        self.strategy
+    }
+
+    pub fn include_system(&self) -> bool {
+        self.include_system
+    }
+
+    pub fn start_on_human(&self) -> bool {
+        self.start_on_human
+    }
+
+    pub fn set_max_tokens(&mut self, max_tokens: usize) {
+        self.max_tokens = max_tokens;
+    }
+
+    pub fn set_strategy(&mut self, strategy: TrimStrategy) {
+        self.strategy = strategy;
+    }
+
+    /// Always keep a leading `SystemMessage`, counting it against the budget.
+    pub fn set_include_system(&mut self, include_system: bool) {
+        self.include_system = include_system;
+    }
+
+    /// Drop messages from the front of the trimmed history until it begins
+    /// with a human turn. A kept leading `SystemMessage` is not affected.
+    pub fn set_start_on_human(&mut self, start_on_human: bool) {
+        self.start_on_human = start_on_human;
+    }
+}
+
+/// Trims `messages` so that their combined count stays within
+/// `options.max_tokens`, using `counter` to size each message. Any fixed
+/// per-request cost the counter reports for an empty conversation is
+/// reserved first.
+///
+/// An `AiMessage` with tool calls and the `ToolMessage`s that follow it are
+/// kept or dropped together, so a trimmed history never holds a tool call
+/// without its results or a result without its call.
+pub fn trim_messages<C>(
+    messages: &[MessageEnum],
+    options: &TrimOptions,
+    counter: &C,
+) -> Vec<MessageEnum>
+where
+    C: TokenCounter + ?Sized,
+{
+    let Some(mut budget) = options.max_tokens.checked_sub(counter.count_messages(&[])) else {
+        return Vec::new();
+    };
+    let mut start = 0;
+    let mut system = None;
+
+    if options.include_system {
+        if let Some(message @ MessageEnum::System(_)) = messages.first() {
+            let tokens = counter.count_message(message);
+            if tokens > budget {
+                return Vec::new();
+            }
+            budget -= tokens;
+            start = 1;
+            system = Some(message);
+        }
+    }
+
+    let blocks = blocks(messages, start);
+    let block_tokens = |block: &Range<usize>| -> usize {
+        messages[block.clone()]
+            .iter()
+            .map(|message| counter.count_message(message))
+            .sum()
+    };
+
+    let mut kept = match options.strategy {
+        TrimStrategy::First => take_within_budget(blocks.iter(), budget, block_tokens),
+        TrimStrategy::Last => {
+            let mut kept = take_within_budget(blocks.iter().rev(), budget, block_tokens);
+            kept.reverse();
+            kept
+        }
+    };
+
+    if options.start_on_human {
+        let first_human = kept
+            .iter()
+            .position(|block| matches!(messages[block.start], MessageEnum::Human(_)))
+            .unwrap_or(kept.len());
+        kept.drain(..first_human);
+    }
+
+    system
+        .into_iter()
+        .chain(kept.into_iter().flat_map(|block| &messages[block]))
+        .cloned()
+        .collect()
+}
+
+fn take_within_budget<'a, I, F>(blocks: I, mut budget: usize, block_tokens: F) -> Vec<Range<usize>>
+where
+    I: Iterator<Item = &'a Range<usize>>,
+    F: Fn(&Range<usize>) -> usize,
+{
+    let mut kept = Vec::new();
+    for block in blocks {
+        let tokens = block_tokens(block);
+        if tokens > budget {
+            break;
+        }
+        budget -= tokens;
+        kept.push(block.clone());
+    }
+    kept
+}
+
+/// Splits `messages[start..]` into units that must not be separated.
+fn blocks(messages: &[MessageEnum], start: usize) -> Vec<Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut index = start;
+
+    while index < messages.len() {
+        let mut end = index + 1;
+        if let MessageEnum::Ai(ai) = &messages[index] {
+            if ai.has_tool_calls() {
+                while matches!(messages.get(end), Some(MessageEnum::Tool(_))) {
+                    end += 1;
+                }
+            }
+        }
+        blocks.push(index..end);
+        index = end;
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::CharRatioCounter;
+    use crate::tool_message::ToolStatus;
+    use crate::{AiMessage, BaseMessage, HumanMessage, SystemMessage, ToolCall, ToolMessage};
+    use serde_json::json;
+
+    fn one_each(_: &MessageEnum) -> usize {
+        1
+    }
+
+    fn contents(messages: &[MessageEnum]) -> Vec<String> {
+        messages
+            .iter()
+            .map(|message| message.content().into_owned())
+            .collect()
+    }
+
+    fn conversation() -> Vec<MessageEnum> {
+        vec![
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+            MessageEnum::Human(HumanMessage::new("Hi")),
+            MessageEnum::Ai(AiMessage::new("Hello!")),
+            MessageEnum::Human(HumanMessage::new("Weather in Paris?")),
+            MessageEnum::Ai(AiMessage::new_with_tool_calls(
+                "",
+                vec![ToolCall::new(
+                    "get_weather",
+                    json!({"city": "Paris"}),
+                    Some("call_1".to_string()),
+                )],
+            )),
+            MessageEnum::Tool(ToolMessage::new(
+                "18 degrees",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+            MessageEnum::Ai(AiMessage::new("It is 18 degrees.")),
+        ]
+    }
+
+    #[test]
+    fn test_trim_last() {
+        let trimmed = trim_messages(&conversation(), &TrimOptions::new(4), &one_each);
+        assert_eq!(
+            contents(&trimmed),
+            vec!["Weather in Paris?", "", "18 degrees", "It is 18 degrees."]
+        );
+    }
+
+    #[test]
+    fn test_trim_first() {
+        let mut options = TrimOptions::new(3);
+        options.set_strategy(TrimStrategy::First);
+
+        let trimmed = trim_messages(&conversation(), &options, &one_each);
+        assert_eq!(contents(&trimmed), vec!["Be brief.", "Hi", "Hello!"]);
+    }
+
+    #[test]
+    fn test_trim_keeps_tool_calls_with_results() {
+        // Two slots would fit the tool result and final answer, but not the
+        // call that produced them, so only the final answer survives.
+        let trimmed = trim_messages(&conversation(), &TrimOptions::new(2), &one_each);
+        assert_eq!(contents(&trimmed), vec!["It is 18 degrees."]);
+
+        let mut options = TrimOptions::new(5);
+        options.set_strategy(TrimStrategy::First);
+        let trimmed = trim_messages(&conversation(), &options, &one_each);
+        assert_eq!(trimmed.len(), 4);
+        assert!(trimmed
+            .iter()
+            .all(|message| !matches!(message, MessageEnum::Tool(_))));
+    }
+
+    #[test]
+    fn test_trim_include_system_and_start_on_human() {
+        let mut options = TrimOptions::new(5);
+        options.set_include_system(true);
+        options.set_start_on_human(true);
+
+        let trimmed = trim_messages(&conversation(), &options, &one_each);
+        assert_eq!(
+            contents(&trimmed),
+            vec![
+                "Be brief.",
+                "Weather in Paris?",
+                "",
+                "18 degrees",
+                "It is 18 degrees."
+            ]
+        );
+
+        options.set_max_tokens(4);
+        let trimmed = trim_messages(&conversation(), &options, &one_each);
+        assert_eq!(contents(&trimmed), vec!["Be brief."]);
+    }
+
+    #[test]
+    fn test_trim_with_content_counter() {
+        let mut options = TrimOptions::new(12);
+        options.set_include_system(true);
+
+        let trimmed = trim_messages(&conversation(), &options, &|message: &MessageEnum| {
+            message.content().len()
+        });
+        assert_eq!(contents(&trimmed), vec!["Be brief."]);
+
+        options.set_max_tokens(8);
+        assert!(
+            trim_messages(&conversation(), &options, &|message: &MessageEnum| {
+                message.content().len()
+            })
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_trim_reserves_conversation_overhead() {
+        let counter = CharRatioCounter::default();
+        let messages = conversation();
+        let last = &messages[messages.len() - 1..];
+
+        // "It is 18 degrees." costs 3 + 1 + 5 = 9, plus 3 for the request.
+        let trimmed = trim_messages(&messages, &TrimOptions::new(12), &counter);
+        assert_eq!(trimmed, last);
+        assert_eq!(counter.count_messages(&trimmed), 12);
+
+        assert!(trim_messages(&messages, &TrimOptions::new(11), &counter).is_empty());
+        assert!(trim_messages(&messages, &TrimOptions::new(2), &counter).is_empty());
+    }
+}
@@ -0,0 +1,466 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::convert::resolve_chat_role;
+use crate::{BaseMessage, MessageEnum};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    /// A `ToolMessage` does not answer an open tool call from the AI turn
+    /// right before it.
+    OrphanToolResult,
+    /// An AI tool call is not followed by a `ToolMessage` answering it.
+    UnansweredToolCall,
+    /// The same tool call id is used by more than one call.
+    DuplicateToolCallId,
+    /// A system message appears after the conversation has started.
+    SystemNotFirst,
+    /// The first message after any system messages is not a human turn.
+    FirstMessageNotHuman,
+    /// Two human turns or two AI turns follow each other.
+    ConsecutiveSameRole,
+    /// A message has no content and, for AI messages, no tool calls.
+    EmptyContent,
+    /// A `ChatMessage` role has no standard equivalent, or a `RemoveMessage`
+    /// was left in the history.
+    UnsupportedRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub index: usize,
+    pub rule: Rule,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{} at message {}: {}",
+            severity, self.index, self.message
+        )
+    }
+}
+
+/// The rules to check and how severe each one is. Presets match what each
+/// provider rejects outright (errors) or silently rewrites (warnings).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleSet {
+    rules: HashMap<Rule, Severity>,
+}
+
+impl RuleSet {
+    /// A rule set with no rules enabled.
+    pub fn new() -> Self {
+        RuleSet::default()
+    }
+
+    /// Every rule as an error.
+    pub fn strict() -> Self {
+        RuleSet::from_rules([
+            (Rule::OrphanToolResult, Severity::Error),
+            (Rule::UnansweredToolCall, Severity::Error),
+            (Rule::DuplicateToolCallId, Severity::Error),
+            (Rule::SystemNotFirst, Severity::Error),
+            (Rule::FirstMessageNotHuman, Severity::Error),
+            (Rule::ConsecutiveSameRole, Severity::Error),
+            (Rule::EmptyContent, Severity::Error),
+            (Rule::UnsupportedRole, Severity::Error),
+        ])
+    }
+
+    pub fn openai() -> Self {
+        RuleSet::from_rules([
+            (Rule::OrphanToolResult, Severity::Error),
+            (Rule::UnansweredToolCall, Severity::Error),
+            (Rule::DuplicateToolCallId, Severity::Error),
+            (Rule::UnsupportedRole, Severity::Error),
+            (Rule::SystemNotFirst, Severity::Warning),
+            (Rule::EmptyContent, Severity::Warning),
+        ])
+    }
+
+    pub fn anthropic() -> Self {
+        RuleSet::from_rules([
+            (Rule::OrphanToolResult, Severity::Error),
+            (Rule::UnansweredToolCall, Severity::Error),
+            (Rule::DuplicateToolCallId, Severity::Error),
+            (Rule::UnsupportedRole, Severity::Error),
+            (Rule::SystemNotFirst, Severity::Error),
+            (Rule::FirstMessageNotHuman, Severity::Error),
+            (Rule::EmptyContent, Severity::Error),
+            (Rule::ConsecutiveSameRole, Severity::Warning),
+        ])
+    }
+
+    pub fn gemini() -> Self {
+        RuleSet::from_rules([
+            (Rule::OrphanToolResult, Severity::Error),
+            (Rule::UnansweredToolCall, Severity::Error),
+            (Rule::DuplicateToolCallId, Severity::Error),
+            (Rule::UnsupportedRole, Severity::Error),
+            (Rule::SystemNotFirst, Severity::Error),
+            (Rule::EmptyContent, Severity::Error),
+            (Rule::FirstMessageNotHuman, Severity::Warning),
+            (Rule::ConsecutiveSameRole, Severity::Warning),
+        ])
+    }
+
+    fn from_rules(rules: impl IntoIterator<Item = (Rule, Severity)>) -> Self {
+        RuleSet {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
+    pub fn severity(&self, rule: Rule) -> Option<Severity> {
+        self.rules.get(&rule).copied()
+    }
+
+    pub fn set_severity(&mut self, rule: Rule, severity: Severity) {
+        self.rules.insert(rule, severity);
+    }
+
+    pub fn disable(&mut self, rule: Rule) {
+        self.rules.remove(&rule);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    System,
+    Human,
+    Ai,
+    Tool,
+}
+
+/// Checks the ordering of a conversation against `rules` and returns every
+/// problem found, sorted by message index.
+pub fn validate_conversation(messages: &[MessageEnum], rules: &RuleSet) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut report = |index: usize, rule: Rule, message: String| {
+        if let Some(severity) = rules.severity(rule) {
+            diagnostics.push(Diagnostic {
+                index,
+                rule,
+                severity,
+                message,
+            });
+        }
+    };
+
+    let mut seen_call_ids = HashSet::new();
+    // Calls from the latest AI turn that still await a result, and the index
+    // of that turn.
+    let mut open_calls: Vec<&str> = Vec::new();
+    let mut open_calls_at = 0;
+    let mut previous: Option<Turn> = None;
+    let mut started = false;
+
+    for (index, message) in messages.iter().enumerate() {
+        let resolved = match (message, resolve_chat_role(index, message)) {
+            (MessageEnum::Remove(_), _) => {
+                report(
+                    index,
+                    Rule::UnsupportedRole,
+                    "RemoveMessage must be applied before the history is sent".to_string(),
+                );
+                continue;
+            }
+            (_, Ok(resolved)) => resolved,
+            (_, Err(_)) => {
+                report(
+                    index,
+                    Rule::UnsupportedRole,
+                    format!("Chat role {:?} has no standard equivalent", message.role()),
+                );
+                continue;
+            }
+        };
+        let turn = match resolved.as_ref() {
+            MessageEnum::System(_) => Turn::System,
+            MessageEnum::Human(_) => Turn::Human,
+            MessageEnum::Ai(_) => Turn::Ai,
+            _ => Turn::Tool,
+        };
+
+        if turn != Turn::Tool && !open_calls.is_empty() {
+            report(
+                open_calls_at,
+                Rule::UnansweredToolCall,
+                format!("Tool calls {} have no result", open_calls.join(", ")),
+            );
+            open_calls.clear();
+        }
+
+        match resolved.as_ref() {
+            MessageEnum::System(_) if started => report(
+                index,
+                Rule::SystemNotFirst,
+                "System message after the conversation started".to_string(),
+            ),
+            MessageEnum::Tool(tool) => {
+                match open_calls.iter().position(|id| *id == tool.tool_call_id()) {
+                    Some(position) => {
+                        open_calls.remove(position);
+                    }
+                    None => report(
+                        index,
+                        Rule::OrphanToolResult,
+                        format!(
+                            "Tool result for {:?} does not answer an open tool call",
+                            tool.tool_call_id()
+                        ),
+                    ),
+                }
+            }
+            _ => {}
+        }
+
+        if let MessageEnum::Ai(ai) = message {
+            for id in ai.tool_calls().iter().filter_map(|call| call.id()) {
+                if !seen_call_ids.insert(id) {
+                    report(
+                        index,
+                        Rule::DuplicateToolCallId,
+                        format!("Tool call id {:?} is used more than once", id),
+                    );
+                }
+                open_calls.push(id);
+            }
+            open_calls_at = index;
+        }
+
+        if turn != Turn::System && !started {
+            started = true;
+            if turn != Turn::Human {
+                report(
+                    index,
+                    Rule::FirstMessageNotHuman,
+                    "Conversation does not start with a human turn".to_string(),
+                );
+            }
+        }
+
+        if matches!(turn, Turn::Human | Turn::Ai) && previous == Some(turn) {
+            report(
+                index,
+                Rule::ConsecutiveSameRole,
+                format!("Consecutive {} turns", resolved.role()),
+            );
+        }
+        previous = Some(turn);
+
+        let has_tool_calls = matches!(message, MessageEnum::Ai(ai) if ai.has_tool_calls());
+        if message.message_content().is_empty() && !has_tool_calls {
+            report(
+                index,
+                Rule::EmptyContent,
+                "Message has no content".to_string(),
+            );
+        }
+    }
+
+    if !open_calls.is_empty() {
+        report(
+            open_calls_at,
+            Rule::UnansweredToolCall,
+            format!("Tool calls {} have no result", open_calls.join(", ")),
+        );
+    }
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.index, diagnostic.rule));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_message::ToolStatus;
+    use crate::{
+        AiMessage, ChatMessage, HumanMessage, RemoveMessage, SystemMessage, ToolCall, ToolMessage,
+    };
+    use serde_json::json;
+
+    fn call(id: &str) -> ToolCall {
+        ToolCall::new("search", json!({"q": id}), Some(id.to_string()))
+    }
+
+    fn tool_result(id: &str) -> MessageEnum {
+        MessageEnum::Tool(ToolMessage::new(
+            "result",
+            id.to_string(),
+            None,
+            ToolStatus::Success,
+        ))
+    }
+
+    fn rules(diagnostics: &[Diagnostic]) -> Vec<(usize, Rule)> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.index, diagnostic.rule))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_conversation() {
+        let messages = vec![
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+            MessageEnum::Human(HumanMessage::new("Search for two things")),
+            MessageEnum::Ai(AiMessage::new_with_tool_calls(
+                "",
+                vec![call("call_1"), call("call_2")],
+            )),
+            tool_result("call_2"),
+            tool_result("call_1"),
+            MessageEnum::Ai(AiMessage::new("Done.")),
+        ];
+
+        assert!(validate_conversation(&messages, &RuleSet::strict()).is_empty());
+    }
+
+    #[test]
+    fn test_tool_pairing() {
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("Hi")),
+            tool_result("call_0"),
+            MessageEnum::Ai(AiMessage::new_with_tool_calls(
+                "",
+                vec![call("call_1"), call("call_2")],
+            )),
+            tool_result("call_1"),
+            MessageEnum::Human(HumanMessage::new("Well?")),
+            tool_result("call_2"),
+            MessageEnum::Ai(AiMessage::new_with_tool_calls("", vec![call("call_1")])),
+        ];
+
+        let diagnostics = validate_conversation(&messages, &RuleSet::openai());
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                (1, Rule::OrphanToolResult),
+                (2, Rule::UnansweredToolCall),
+                (5, Rule::OrphanToolResult),
+                (6, Rule::UnansweredToolCall),
+                (6, Rule::DuplicateToolCallId),
+            ]
+        );
+        assert_eq!(diagnostics[1].message, "Tool calls call_2 have no result");
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_ordering_rules_per_provider() {
+        let messages = vec![
+            MessageEnum::Ai(AiMessage::new("Welcome!")),
+            MessageEnum::Human(HumanMessage::new("Hi")),
+            MessageEnum::Human(HumanMessage::new("")),
+            MessageEnum::System(SystemMessage::new("Be brief.")),
+        ];
+
+        let anthropic = validate_conversation(&messages, &RuleSet::anthropic());
+        assert_eq!(
+            rules(&anthropic),
+            vec![
+                (0, Rule::FirstMessageNotHuman),
+                (2, Rule::ConsecutiveSameRole),
+                (2, Rule::EmptyContent),
+                (3, Rule::SystemNotFirst),
+            ]
+        );
+        assert_eq!(anthropic[1].severity, Severity::Warning);
+
+        let openai = validate_conversation(&messages, &RuleSet::openai());
+        assert_eq!(
+            openai
+                .iter()
+                .map(|diagnostic| (diagnostic.rule, diagnostic.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                (Rule::EmptyContent, Severity::Warning),
+                (Rule::SystemNotFirst, Severity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chat_roles_and_removals() {
+        let messages = vec![
+            MessageEnum::Chat(ChatMessage::new("Hi", "user".to_string())),
+            MessageEnum::Chat(ChatMessage::new("Hello", "assistant".to_string())),
+            MessageEnum::Chat(ChatMessage::new("Nice", "critic".to_string())),
+            MessageEnum::Remove(RemoveMessage::with_id("msg_1")),
+        ];
+
+        let diagnostics = validate_conversation(&messages, &RuleSet::anthropic());
+        assert_eq!(
+            rules(&diagnostics),
+            vec![(2, Rule::UnsupportedRole), (3, Rule::UnsupportedRole)]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error at message 2: Chat role \"critic\" has no standard equivalent"
+        );
+    }
+
+    #[test]
+    fn test_custom_rule_set() {
+        let messages = vec![
+            MessageEnum::Human(HumanMessage::new("Hi")),
+            MessageEnum::Human(HumanMessage::new("Anyone there?")),
+        ];
+
+        let mut rules = RuleSet::gemini();
+        assert_eq!(
+            validate_conversation(&messages, &rules)[0].severity,
+            Severity::Warning
+        );
+
+        rules.set_severity(Rule::ConsecutiveSameRole, Severity::Error);
+        assert_eq!(
+            validate_conversation(&messages, &rules)[0].severity,
+            Severity::Error
+        );
+
+        rules.disable(Rule::ConsecutiveSameRole);
+        assert!(validate_conversation(&messages, &rules).is_empty());
+        assert!(validate_conversation(&messages, &RuleSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_diagnostic_serialization() {
+        let diagnostic = Diagnostic {
+            index: 3,
+            rule: Rule::SystemNotFirst,
+            severity: Severity::Error,
+            message: "System message after the conversation started".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&diagnostic).unwrap(),
+            json!({
+                "index": 3,
+                "rule": "system_not_first",
+                "severity": "error",
+                "message": "System message after the conversation started"
+            })
+        );
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use messageforge::convert::anthropic::{
+        from_anthropic_response, to_anthropic_request, AnthropicResponse, CONTENT_BLOCKS_KEY,
+    };
+    use messageforge::tool_message::ToolStatus;
+    use messageforge::{
+        AiMessage, BaseMessage, HumanMessage, MessageEnum, SystemMessage, ToolCall, ToolMessage,
+    };
+    use serde_json::{json, Value};
+
+    const REQUEST_WITH_TOOLS: &str = include_str!("fixtures/anthropic/request_with_tools.json");
+    const RESPONSE_TOOL_USE: &str = include_str!("fixtures/anthropic/response_tool_use.json");
+    const RESPONSE_THINKING_TOOL_USE: &str =
+        include_str!("fixtures/anthropic/response_thinking_tool_use.json");
+
+    #[test]
+    fn test_request_with_tools() {
+        let conversation = vec![
+            MessageEnum::System(SystemMessage::new("You are a helpful weather assistant.")),
+            MessageEnum::Human(HumanMessage::new("What's the weather in Paris and Tokyo?")),
+            MessageEnum::Ai(AiMessage::new_with_tool_calls(
+                "Let me check both cities.",
+                vec![
+                    ToolCall::new(
+                        "get_weather",
+                        json!({"city": "Paris"}),
+                        Some("toolu_01".to_string()),
+                    ),
+                    ToolCall::new(
+                        "get_weather",
+                        json!({"city": "Tokyo"}),
+                        Some("toolu_02".to_string()),
+                    ),
+                ],
+            )),
+            MessageEnum::Tool(ToolMessage::new(
+                "18 degrees and sunny",
+                "toolu_01".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+            MessageEnum::Tool(ToolMessage::new(
+                "Weather service unavailable",
+                "toolu_02".to_string(),
+                None,
+                ToolStatus::Error,
+            )),
+            MessageEnum::Human(HumanMessage::new("Just give me Paris then.")),
+        ];
+
+        let request = to_anthropic_request(&conversation).unwrap();
+        let expected: Value = serde_json::from_str(REQUEST_WITH_TOOLS).unwrap();
+        assert_eq!(serde_json::to_value(&request).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_response_tool_use() {
+        let response: AnthropicResponse = serde_json::from_str(RESPONSE_TOOL_USE).unwrap();
+
+        let ai = from_anthropic_response(&response).unwrap();
+        assert_eq!(ai.id(), Some("msg_01Aq9w938a90dw8q"));
+        assert_eq!(ai.content(), "I'll look that up.");
+
+        assert_eq!(ai.tool_calls().len(), 1);
+        assert_eq!(ai.tool_calls()[0].name(), "get_weather");
+        assert_eq!(
+            ai.tool_calls()[0].id(),
+            Some("toolu_01A09q90qw90lq917835lq9")
+        );
+        assert_eq!(
+            ai.tool_calls()[0].args(),
+            &json!({"city": "Paris", "unit": "celsius"})
+        );
+
+        assert_eq!(
+            ai.response_metadata().get("stop_reason"),
+            Some(&"tool_use".into())
+        );
+        assert_eq!(
+            ai.response_metadata().get("model_name"),
+            Some(&"claude-sonnet-4-20250514".into())
+        );
+
+        let usage = ai.usage_metadata().unwrap();
+        assert_eq!(usage.input_tokens, 600);
+        assert_eq!(usage.output_tokens, 89);
+        assert_eq!(usage.cached_input_tokens(), 128);
+    }
+
+    #[test]
+    fn test_thinking_blocks_are_sent_back() {
+        let response: AnthropicResponse = serde_json::from_str(RESPONSE_THINKING_TOOL_USE).unwrap();
+        let fixture: Value = serde_json::from_str(RESPONSE_THINKING_TOOL_USE).unwrap();
+
+        let ai = from_anthropic_response(&response).unwrap();
+        assert_eq!(ai.content(), "");
+        assert_eq!(
+            ai.tool_calls()[0].id(),
+            Some("toolu_01CswdEQBMshySk6Y9DFKrfq")
+        );
+        let blocks = &fixture["content"];
+        assert_eq!(
+            ai.additional_kwargs()[CONTENT_BLOCKS_KEY],
+            json!([
+                {"index": 0, "block": blocks[0]},
+                {"index": 1, "block": blocks[1]}
+            ])
+        );
+
+        let conversation = vec![
+            MessageEnum::Human(HumanMessage::new("What's the weather in Paris?")),
+            MessageEnum::Ai(ai),
+            MessageEnum::Tool(ToolMessage::new(
+                "18 degrees",
+                "toolu_01CswdEQBMshySk6Y9DFKrfq".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+        ];
+
+        let request = to_anthropic_request(&conversation).unwrap();
+        let serialized = serde_json::to_value(&request).unwrap();
+        assert_eq!(serialized["messages"][1]["content"], fixture["content"]);
+    }
+}
@@ -46,21 +46,18 @@ mod tests {
         msg.base.example = true;
         msg.base
             .additional_kwargs
-            .insert("key".to_string(), "value".to_string());
+            .insert("key".to_string(), "value".into());
         msg.base
             .response_metadata
-            .insert("token_count".to_string(), "42".to_string());
+            .insert("token_count".to_string(), "42".into());
         msg.base.id = Some("12345".to_string());
         msg.base.name = Some("User".to_string());
 
         assert!(msg.base.example);
-        assert_eq!(
-            msg.base.additional_kwargs.get("key"),
-            Some(&"value".to_string())
-        );
+        assert_eq!(msg.base.additional_kwargs.get("key"), Some(&"value".into()));
         assert_eq!(
             msg.base.response_metadata.get("token_count"),
-            Some(&"42".to_string())
+            Some(&"42".into())
         );
         assert_eq!(msg.base.id, Some("12345".to_string()));
         assert_eq!(msg.base.name, Some("User".to_string()));
@@ -98,11 +95,11 @@ mod tests {
 
         msg.base
             .additional_kwargs
-            .insert("test_key".to_string(), "test_value".to_string());
+            .insert("test_key".to_string(), "test_value".into());
 
         assert_eq!(
             msg.base.additional_kwargs.get("test_key"),
-            Some(&"test_value".to_string())
+            Some(&"test_value".into())
         );
     }
 
@@ -140,22 +137,22 @@ mod tests {
         human_message
             .base
             .additional_kwargs
-            .insert("key".to_string(), "value".to_string());
+            .insert("key".to_string(), "value".into());
         human_message
             .base
             .response_metadata
-            .insert("metadata_key".to_string(), "metadata_value".to_string());
+            .insert("metadata_key".to_string(), "metadata_value".into());
         human_message.base.id = Some("12345".to_string());
         human_message.base.name = Some("Test User".to_string());
 
         assert!(human_message.is_example());
         assert_eq!(
             human_message.additional_kwargs().get("key"),
-            Some(&"value".to_string())
+            Some(&"value".into())
         );
         assert_eq!(
             human_message.response_metadata().get("metadata_key"),
-            Some(&"metadata_value".to_string())
+            Some(&"metadata_value".into())
         );
         assert_eq!(human_message.id(), Some("12345"));
         assert_eq!(human_message.name(), Some("Test User"));
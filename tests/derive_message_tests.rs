@@ -29,3 +29,23 @@ mod tests {
         assert_eq!(msg.message_type(), &MessageType::Chat);
     }
 }
+
+#[cfg(test)]
+mod without_message_content_import {
+    use derive_base_message::BaseMessage;
+    use messageforge::{BaseMessage, BaseMessageFields, MessageType};
+
+    #[derive(BaseMessage)]
+    pub struct ChatMessage {
+        pub role: String,
+        pub base: BaseMessageFields,
+    }
+
+    #[test]
+    fn test_derive_does_not_need_message_content_in_scope() {
+        let mut msg = ChatMessage::new("Hello, world!", "Admin".to_string());
+        msg.set_content("Updated");
+        assert_eq!(msg.content(), "Updated");
+        assert_eq!(msg.message_type(), &MessageType::Chat);
+    }
+}
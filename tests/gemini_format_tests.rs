@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use messageforge::convert::gemini::{
+        from_gemini_request, from_gemini_response, to_gemini_request, GeminiResponse,
+        REASONING_CONTENT_KEY, THOUGHT_SIGNATURES_KEY,
+    };
+    use messageforge::tool_message::ToolStatus;
+    use messageforge::utils::{validate_conversation, RuleSet};
+    use messageforge::{
+        AiMessage, BaseMessage, ContentBlock, HumanMessage, MessageEnum, SystemMessage, ToolMessage,
+    };
+    use serde_json::{json, Value};
+
+    const RESPONSE_FUNCTION_CALL: &str =
+        include_str!("fixtures/gemini/response_function_call.json");
+    const RESPONSE_THINKING_FUNCTION_CALL: &str =
+        include_str!("fixtures/gemini/response_thinking_function_call.json");
+
+    #[test]
+    fn test_response_function_call() {
+        let response: GeminiResponse = serde_json::from_str(RESPONSE_FUNCTION_CALL).unwrap();
+
+        let ai = from_gemini_response(&response).unwrap();
+        assert_eq!(ai.id(), Some("mF2QaOr5M5a1nvgP"));
+        assert_eq!(ai.content(), "Checking the forecast.");
+        assert_eq!(ai.tool_calls()[0].name(), "get_weather");
+        assert_eq!(ai.tool_calls()[0].args(), &json!({"city": "Paris"}));
+        assert_eq!(
+            ai.response_metadata().get("finish_reason"),
+            Some(&"STOP".into())
+        );
+
+        let usage = ai.usage_metadata().unwrap();
+        assert_eq!(usage.input_tokens, 58);
+        assert_eq!(usage.output_tokens, 37);
+        assert_eq!(usage.total_tokens, 95);
+        assert_eq!(usage.cached_input_tokens(), 32);
+        assert_eq!(usage.reasoning_tokens(), 25);
+    }
+
+    #[test]
+    fn test_conversation_round_trip() {
+        let response: GeminiResponse = serde_json::from_str(RESPONSE_FUNCTION_CALL).unwrap();
+        let ai = from_gemini_response(&response).unwrap();
+        let call_id = ai.tool_calls()[0].id().unwrap().to_string();
+
+        let conversation = vec![
+            MessageEnum::System(SystemMessage::new("You are a weather bot.")),
+            MessageEnum::Human(HumanMessage::new(vec![
+                ContentBlock::text("What's the weather here?"),
+                ContentBlock::image_base64("image/png", "iVBORw0KGgo="),
+            ])),
+            MessageEnum::Ai(ai),
+            MessageEnum::Tool(ToolMessage::new(
+                "{\"temperature\":18}",
+                call_id,
+                None,
+                ToolStatus::Success,
+            )),
+        ];
+
+        let request = to_gemini_request(&conversation).unwrap();
+        let serialized = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            serialized["contents"][0]["parts"][1],
+            json!({"inlineData": {"mimeType": "image/png", "data": "iVBORw0KGgo="}})
+        );
+        assert_eq!(
+            serialized["contents"][2]["parts"][0]["functionResponse"]["response"],
+            json!({"temperature": 18})
+        );
+
+        let round_trip = from_gemini_request(&request).unwrap();
+        assert_eq!(round_trip.len(), 4);
+        assert_eq!(round_trip[1], conversation[1]);
+        assert_eq!(round_trip[3].content(), "{\"temperature\":18}");
+        assert_eq!(round_trip[3].name(), Some("get_weather"));
+    }
+
+    #[test]
+    fn test_thought_signature_is_echoed_back() {
+        let response: GeminiResponse =
+            serde_json::from_str(RESPONSE_THINKING_FUNCTION_CALL).unwrap();
+
+        let ai = from_gemini_response(&response).unwrap();
+        assert_eq!(ai.content(), "");
+        let call_id = ai.tool_calls()[0].id().unwrap().to_string();
+        let signature = &response.candidates[0].content.parts[1].thought_signature;
+        assert_eq!(
+            ai.additional_kwargs()[THOUGHT_SIGNATURES_KEY][&call_id],
+            json!(signature)
+        );
+        assert!(ai.additional_kwargs()[REASONING_CONTENT_KEY]
+            .as_str()
+            .unwrap()
+            .starts_with("**Checking the forecast**"));
+        assert_eq!(ai.usage_metadata().unwrap().reasoning_tokens(), 76);
+
+        let conversation = vec![
+            MessageEnum::Human(HumanMessage::new("What's the weather in Paris?")),
+            MessageEnum::Ai(ai),
+            MessageEnum::Tool(ToolMessage::new(
+                "{\"temperature\":18}",
+                call_id,
+                None,
+                ToolStatus::Success,
+            )),
+        ];
+
+        let request = to_gemini_request(&conversation).unwrap();
+        let serialized = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            serialized["contents"][1],
+            json!({
+                "role": "model",
+                "parts": [{
+                    "functionCall": {
+                        "name": "get_weather",
+                        "args": {"city": "Paris"}
+                    },
+                    "thoughtSignature": signature
+                }]
+            })
+        );
+        let round_trip = from_gemini_request(&request).unwrap();
+        let round_trip_call = round_trip[1].as_ai().unwrap().tool_calls()[0].id().unwrap();
+        assert_eq!(
+            round_trip[1].additional_kwargs()[THOUGHT_SIGNATURES_KEY][round_trip_call],
+            json!(signature)
+        );
+        assert_eq!(
+            round_trip[2].as_tool().unwrap().tool_call_id(),
+            round_trip_call
+        );
+    }
+
+    #[test]
+    fn test_responses_without_call_ids_get_unique_ids() {
+        let mut second: Value = serde_json::from_str(RESPONSE_FUNCTION_CALL).unwrap();
+        second["responseId"] = json!("nG3QaPr7K8b2owgQ");
+        let mut third = second.clone();
+        third.as_object_mut().unwrap().remove("responseId");
+
+        let responses = [
+            serde_json::from_str(RESPONSE_FUNCTION_CALL).unwrap(),
+            serde_json::from_value(second).unwrap(),
+            serde_json::from_value(third.clone()).unwrap(),
+            serde_json::from_value(third).unwrap(),
+        ];
+        let mut conversation = vec![MessageEnum::Human(HumanMessage::new("Weather?"))];
+        for response in &responses {
+            let ai: AiMessage = from_gemini_response(response).unwrap();
+            let call_id = ai.tool_calls()[0].id().unwrap().to_string();
+            conversation.push(MessageEnum::Ai(ai));
+            conversation.push(MessageEnum::Tool(ToolMessage::new(
+                "{\"temperature\":18}",
+                call_id,
+                None,
+                ToolStatus::Success,
+            )));
+        }
+
+        assert_eq!(
+            conversation[1].as_ai().unwrap().tool_calls()[0].id(),
+            Some("call_mF2QaOr5M5a1nvgP_1")
+        );
+        assert!(validate_conversation(&conversation, &RuleSet::gemini()).is_empty());
+
+        let request = serde_json::to_value(to_gemini_request(&conversation).unwrap()).unwrap();
+        for content in request["contents"].as_array().unwrap() {
+            for part in content["parts"].as_array().unwrap() {
+                for key in ["functionCall", "functionResponse"] {
+                    if let Some(call) = part.get(key) {
+                        assert!(call.get("id").is_none());
+                    }
+                }
+            }
+        }
+    }
+}
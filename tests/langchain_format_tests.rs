@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use messageforge::convert::langchain::{
+        dumpd, dumps, load, loads, messages_from_dict, messages_to_dict, ARTIFACT_KEY,
+    };
+    use messageforge::tool_message::ToolStatus;
+    use messageforge::{BaseMessage, ContentBlock, MediaSource, MessageEnum};
+    use serde_json::{json, Value};
+
+    const DUMPD_CONVERSATION: &str = include_str!("fixtures/langchain/dumpd_conversation.json");
+    const MESSAGES_TO_DICT: &str = include_str!("fixtures/langchain/messages_to_dict.json");
+    const MULTIMODAL_ARTIFACT: &str = include_str!("fixtures/langchain/multimodal_artifact.json");
+
+    #[test]
+    fn test_dumpd_conversation_round_trip() {
+        let fixture: Vec<Value> = serde_json::from_str(DUMPD_CONVERSATION).unwrap();
+
+        let messages = fixture
+            .iter()
+            .map(load)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let human = messages[1].as_human().unwrap();
+        assert_eq!(human.name(), Some("alice"));
+        assert_eq!(
+            human.message_content().blocks()[1],
+            ContentBlock::image_url("https://example.com/paris.jpg")
+        );
+
+        let ai = messages[2].as_ai().unwrap();
+        assert_eq!(ai.tool_calls()[0].args(), &json!({"city": "Paris"}));
+        assert_eq!(ai.usage_metadata().unwrap().cached_input_tokens(), 64);
+        assert_eq!(
+            ai.response_metadata().get("finish_reason"),
+            Some(&"tool_calls".into())
+        );
+
+        let dumped: Vec<Value> = messages.iter().map(dumpd).collect();
+        assert_eq!(dumped, fixture);
+    }
+
+    #[test]
+    fn test_messages_to_dict_round_trip() {
+        let fixture: Vec<Value> = serde_json::from_str(MESSAGES_TO_DICT).unwrap();
+
+        let messages = messages_from_dict(&fixture).unwrap();
+        assert_eq!(messages.len(), 4);
+
+        let ai = messages[1].as_ai().unwrap();
+        assert_eq!(ai.invalid_tool_calls()[0].id.as_deref(), Some("toolu_02"));
+
+        let tool = messages[2].as_tool().unwrap();
+        assert_eq!(tool.status(), &ToolStatus::Error);
+
+        assert_eq!(messages_to_dict(&messages), fixture);
+    }
+
+    #[test]
+    fn test_dumps_loads_round_trip() {
+        let fixture: Vec<Value> = serde_json::from_str(MESSAGES_TO_DICT).unwrap();
+        let messages = messages_from_dict(&fixture).unwrap();
+
+        for message in messages {
+            let serialized = dumps(&message).unwrap();
+            let loaded: MessageEnum = loads(&serialized).unwrap();
+            assert_eq!(loaded, message);
+        }
+    }
+
+    #[test]
+    fn test_image_parts_and_artifacts_round_trip() {
+        let fixture: Vec<Value> = serde_json::from_str(MULTIMODAL_ARTIFACT).unwrap();
+
+        let messages = messages_from_dict(&fixture).unwrap();
+        let human = messages[0].as_human().unwrap();
+        assert_eq!(
+            human.message_content().blocks()[1],
+            ContentBlock::Image {
+                source: MediaSource::Url {
+                    url: "https://example.com/paris.jpg".to_string()
+                },
+                detail: Some("high".to_string()),
+            }
+        );
+        assert_eq!(
+            human.message_content().blocks()[2],
+            ContentBlock::image_base64("image/png", "iVBORw0KGgo=")
+        );
+
+        let ai = messages[1].as_ai().unwrap();
+        assert_eq!(ai.content(), "Let me look up the landmarks.");
+
+        let tool = messages[2].as_tool().unwrap();
+        assert_eq!(tool.artifact(), &None);
+        assert_eq!(
+            tool.additional_kwargs()[ARTIFACT_KEY]["landmarks"][0]["name"],
+            "Eiffel Tower"
+        );
+
+        assert_eq!(messages_to_dict(&messages), fixture);
+        for message in &messages {
+            assert_eq!(&loads(&dumps(message).unwrap()).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_edited_content_replaces_original() {
+        let fixture: Vec<Value> = serde_json::from_str(MULTIMODAL_ARTIFACT).unwrap();
+        let mut messages = messages_from_dict(&fixture).unwrap();
+
+        if let MessageEnum::Human(human) = &mut messages[0] {
+            human.set_content("Which photo shows Paris?");
+        }
+        let dumped = messages_to_dict(&messages);
+        assert_eq!(dumped[0]["data"]["content"], "Which photo shows Paris?");
+        assert_eq!(dumped[0]["data"]["additional_kwargs"], json!({}));
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use messageforge::convert::markdown::{from_markdown, to_markdown};
+    use messageforge::tool_message::ToolStatus;
+    use messageforge::{
+        AiMessage, BaseMessage, ChatMessage, HumanMessage, MessageEnum, SystemMessage, ToolCall,
+        ToolMessage,
+    };
+    use serde_json::json;
+
+    const CONVERSATION: &str = include_str!("fixtures/markdown/conversation.md");
+
+    fn conversation() -> Vec<MessageEnum> {
+        let mut human = HumanMessage::new("What's the weather in Paris and Tokyo?");
+        human.set_id(Some("msg_1".to_string()));
+        human.set_name(Some("ada".to_string()));
+
+        let mut ai = AiMessage::new_with_tool_calls(
+            "Let me check both cities.",
+            vec![
+                ToolCall::new(
+                    "get_weather",
+                    json!({"city": "Paris"}),
+                    Some("call_1".to_string()),
+                ),
+                ToolCall::new(
+                    "get_weather",
+                    json!({"city": "Tokyo"}),
+                    Some("call_2".to_string()),
+                ),
+            ],
+        );
+        ai.set_id(Some("msg_2".to_string()));
+
+        vec![
+            MessageEnum::System(SystemMessage::new("You are a helpful weather assistant.")),
+            MessageEnum::Human(human),
+            MessageEnum::Ai(ai),
+            MessageEnum::Tool(ToolMessage::new(
+                "18 degrees and sunny",
+                "call_1".to_string(),
+                None,
+                ToolStatus::Success,
+            )),
+            MessageEnum::Tool(ToolMessage::new(
+                "Weather service unavailable",
+                "call_2".to_string(),
+                None,
+                ToolStatus::Error,
+            )),
+            MessageEnum::Ai(AiMessage::new(
+                "Paris is 18 degrees and sunny.\n\nI couldn't reach the service for Tokyo.",
+            )),
+            MessageEnum::Chat(ChatMessage::new("Accurate.", "reviewer".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_export_conversation() {
+        assert_eq!(to_markdown(&conversation()).unwrap(), CONVERSATION);
+    }
+
+    #[test]
+    fn test_import_conversation() {
+        let messages = from_markdown(CONVERSATION).unwrap();
+        assert_eq!(messages, conversation());
+        assert_eq!(messages[1].name(), Some("ada"));
+        assert_eq!(messages[4].as_tool().unwrap().status(), &ToolStatus::Error);
+    }
+}
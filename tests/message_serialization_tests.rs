@@ -11,11 +11,11 @@ mod tests {
         human_message
             .base
             .additional_kwargs
-            .insert("key1".to_string(), "value1".to_string());
+            .insert("key1".to_string(), "value1".into());
         human_message
             .base
             .response_metadata
-            .insert("meta1".to_string(), "metadata1".to_string());
+            .insert("meta1".to_string(), "metadata1".into());
         human_message.base.id = Some("12345".to_string());
         human_message.base.name = Some("John Doe".to_string());
 
@@ -50,11 +50,11 @@ mod tests {
         assert!(!deserialized.is_example());
         assert_eq!(
             deserialized.additional_kwargs().get("key1"),
-            Some(&"value1".to_string())
+            Some(&"value1".into())
         );
         assert_eq!(
             deserialized.response_metadata().get("meta1"),
-            Some(&"metadata1".to_string())
+            Some(&"metadata1".into())
         );
         assert_eq!(deserialized.id(), Some("12345"));
         assert_eq!(deserialized.name(), Some("John Doe"));
@@ -101,4 +101,34 @@ mod tests {
             deserialized
         );
     }
+
+    #[test]
+    fn test_nested_metadata_round_trip() {
+        let json_str = r#"
+        {
+            "content": "Hello, nested!",
+            "message_type": "Human",
+            "additional_kwargs": {"legacy": "plain string", "tags": ["a", "b"]},
+            "response_metadata": {"safety": {"blocked": false, "score": 0.25}}
+        }
+        "#;
+
+        let deserialized: HumanMessage = serde_json::from_str(json_str).unwrap();
+
+        assert_eq!(deserialized.additional_kwargs()["legacy"], "plain string");
+        assert_eq!(
+            deserialized
+                .get_additional_kwarg::<Vec<String>>("tags")
+                .unwrap(),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(
+            deserialized.response_metadata()["safety"]["score"],
+            serde_json::json!(0.25)
+        );
+
+        let serialized = serde_json::to_string(&deserialized).unwrap();
+        assert!(serialized.contains("\"legacy\":\"plain string\""));
+        assert!(serialized.contains("\"safety\":{"));
+    }
 }
@@ -9,7 +9,7 @@ fn test_message_integration() {
     assert_eq!(ai_msg.message_type(), &MessageType::Ai);
 
     let ai_msg_debug_output = format!("{:?}", ai_msg);
-    let expected_ai_msg_debug = r#"AiMessage { base: BaseMessageFields { content: "This is an AI response", example: false, message_type: Ai, additional_kwargs: {}, response_metadata: {}, id: None, name: None } }"#;
+    let expected_ai_msg_debug = r#"AiMessage { tool_calls: [], invalid_tool_calls: [], usage_metadata: None, base: BaseMessageFields { content: "This is an AI response", example: false, message_type: Ai, additional_kwargs: {}, response_metadata: {}, id: None, name: None } }"#;
     assert_eq!(ai_msg_debug_output, expected_ai_msg_debug);
 
     let chat_msg = ChatMessage::new("Hello from Chat!", "User".to_string());
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use messageforge::convert::openai::{
+        from_openai_completion, from_openai_messages, to_openai_messages, OpenAiChatCompletion,
+        OpenAiMessage,
+    };
+    use messageforge::{BaseMessage, ContentBlock, MediaSource, MessageEnum};
+    use serde_json::{json, Value};
+
+    const CONVERSATION_REQUEST: &str = include_str!("fixtures/openai/conversation_request.json");
+    const COMPLETION_TOOL_CALLS: &str = include_str!("fixtures/openai/completion_tool_calls.json");
+    const MULTIMODAL_USER: &str = include_str!("fixtures/openai/multimodal_user.json");
+
+    #[test]
+    fn test_conversation_request_round_trip() {
+        let fixture: Vec<OpenAiMessage> = serde_json::from_str(CONVERSATION_REQUEST).unwrap();
+
+        let messages = from_openai_messages(&fixture).unwrap();
+        assert_eq!(messages.len(), 5);
+        assert!(matches!(messages[0], MessageEnum::System(_)));
+        assert_eq!(messages[1].name(), Some("alice"));
+
+        let ai = messages[2].as_ai().unwrap();
+        assert_eq!(ai.tool_calls()[0].name(), "get_weather");
+        assert_eq!(
+            ai.tool_calls()[0].args(),
+            &json!({"city": "Paris", "unit": "celsius"})
+        );
+
+        let tool = messages[3].as_tool().unwrap();
+        assert_eq!(tool.tool_call_id(), "call_abc123");
+
+        let converted = to_openai_messages(&messages).unwrap();
+        let expected: Value = serde_json::from_str(CONVERSATION_REQUEST).unwrap();
+        assert_eq!(serde_json::to_value(&converted).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_completion_with_tool_calls() {
+        let completion: OpenAiChatCompletion = serde_json::from_str(COMPLETION_TOOL_CALLS).unwrap();
+
+        let ai = from_openai_completion(&completion).unwrap();
+        assert_eq!(ai.id(), Some("chatcmpl-9xYz"));
+        assert_eq!(ai.content(), "");
+
+        assert_eq!(ai.tool_calls().len(), 1);
+        assert_eq!(ai.tool_calls()[0].id(), Some("call_1"));
+        assert_eq!(ai.tool_calls()[0].args(), &json!({"city": "Paris"}));
+
+        assert_eq!(ai.invalid_tool_calls().len(), 1);
+        assert_eq!(ai.invalid_tool_calls()[0].name.as_deref(), Some("get_time"));
+        assert!(ai.invalid_tool_calls()[0].error.is_some());
+
+        assert_eq!(
+            ai.response_metadata().get("model_name"),
+            Some(&"gpt-4o-2024-08-06".into())
+        );
+        assert_eq!(
+            ai.response_metadata().get("finish_reason"),
+            Some(&"tool_calls".into())
+        );
+
+        let usage = ai.usage_metadata().unwrap();
+        assert_eq!(usage.input_tokens, 82);
+        assert_eq!(usage.output_tokens, 17);
+        assert_eq!(usage.total_tokens, 99);
+        assert_eq!(usage.cached_input_tokens(), 64);
+    }
+
+    #[test]
+    fn test_multimodal_user_round_trip() {
+        let fixture: Vec<OpenAiMessage> = serde_json::from_str(MULTIMODAL_USER).unwrap();
+
+        let messages = from_openai_messages(&fixture).unwrap();
+        let human = messages[0].as_human().unwrap();
+        let blocks = human.message_content().blocks();
+
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(blocks[0], ContentBlock::text("Compare these two images."));
+        assert_eq!(
+            blocks[2],
+            ContentBlock::Image {
+                source: MediaSource::Base64 {
+                    mime_type: "image/jpeg".to_string(),
+                    data: "/9j/4AAQSkZJRg==".to_string(),
+                },
+                detail: None,
+            }
+        );
+        assert_eq!(
+            blocks[4],
+            ContentBlock::file_id("file-abc123", Some("report.pdf".to_string()))
+        );
+
+        let converted = to_openai_messages(&messages).unwrap();
+        let expected: Value = serde_json::from_str(MULTIMODAL_USER).unwrap();
+        assert_eq!(serde_json::to_value(&converted).unwrap(), expected);
+    }
+}